@@ -0,0 +1,223 @@
+use std::io::Error as IoError;
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader as AsyncBufReader};
+use tokio::task;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+use crate::shared::with_thread_rng;
+use crate::snapshot::SnapshotBrain;
+
+impl Brain {
+    /// Create a brain from a dataset, without blocking the async runtime.
+    ///
+    /// See [`Brain::from_dataset`].
+    ///
+    /// [`Brain::from_dataset`]: crate::brain::Brain::from_dataset
+    pub async fn from_dataset_async(&mut self, dataset_path: impl AsRef<str>) -> Result<&mut Self> {
+        let dataset_path = dataset_path.as_ref();
+
+        println!("Getting a new brain from '{}`...", dataset_path);
+        let dataset_file = tokio::fs::File::open(dataset_path).await?;
+
+        self.ingest_reader(dataset_file).await
+    }
+
+    /// Learn every line read from `reader`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// # async fn run() -> rustkov::prelude::Result<()> {
+    /// let mut brain = Brain::new();
+    ///
+    /// brain.ingest_reader(&b"Hello there!\nGeneral Kenobi!"[..]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ingest_reader(&mut self, reader: impl AsyncRead + Unpin) -> Result<&mut Self> {
+        let mut lines = AsyncBufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            self.ingest(&line);
+        }
+        Ok(self)
+    }
+
+    /// Save the current brain to disk, without blocking the async runtime.
+    ///
+    /// See [`Brain::to_file`].
+    ///
+    /// [`Brain::to_file`]: crate::brain::Brain::to_file
+    pub async fn to_file_async(&self, output_path: impl AsRef<str>) -> Result<()> {
+        let output_path = output_path.as_ref();
+        println!("Saving brain...");
+
+        tokio::fs::write(output_path, self.to_bytes()).await?;
+
+        println!("Saved brain as {}", output_path);
+        Ok(())
+    }
+
+    /// Load a brain from disk, without blocking the async runtime.
+    ///
+    /// See [`Brain::from_file`].
+    ///
+    /// [`Brain::from_file`]: crate::brain::Brain::from_file
+    pub async fn from_file_async(brain_path: impl AsRef<str>) -> Result<Self> {
+        let brain_path = brain_path.as_ref();
+
+        println!("Loading brain from {}...", brain_path);
+        let buffer = tokio::fs::read(brain_path).await?;
+
+        Ok(Self::from_bytes(&buffer))
+    }
+}
+
+/// A brain shared between async tasks.
+///
+/// Replies are generated right away from the current version of the brain,
+/// as with [`SnapshotBrain`], while loading, saving and learning are done
+/// on blocking threads, so they never stall the async runtime.
+///
+/// Cloning an `AsyncBrain` gives another handle to the same brain.
+///
+/// # Example
+///
+/// ```no_run
+/// use rustkov::prelude::AsyncBrain;
+///
+/// # async fn run() -> rustkov::prelude::Result<()> {
+/// let brain = AsyncBrain::from_file("brain.bin").await?;
+///
+/// if let Some(response) = brain.generate("Hello there!")? {
+///     println!("{}", response);
+/// }
+///
+/// brain.learn(["Hello there!"]).await?;
+/// brain.save("brain.bin").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SnapshotBrain`]: crate::snapshot::SnapshotBrain
+#[derive(Debug, Clone)]
+pub struct AsyncBrain {
+    snapshots: SnapshotBrain,
+}
+
+impl AsyncBrain {
+    /// Share a brain between async tasks.
+    pub fn new(brain: Brain) -> Self {
+        Self {
+            snapshots: SnapshotBrain::new(brain),
+        }
+    }
+
+    /// Load a brain from disk on a blocking thread.
+    ///
+    /// See [`Brain::from_file`].
+    ///
+    /// [`Brain::from_file`]: crate::brain::Brain::from_file
+    pub async fn from_file(brain_path: impl Into<String>) -> Result<Self> {
+        let brain_path = brain_path.into();
+        let brain = blocking(move || Brain::from_file(brain_path)).await?;
+
+        Ok(Self::new(brain))
+    }
+
+    /// Save the current version of the brain to disk on a blocking thread.
+    ///
+    /// See [`Brain::to_file`].
+    ///
+    /// [`Brain::to_file`]: crate::brain::Brain::to_file
+    pub async fn save(&self, output_path: impl Into<String>) -> Result<()> {
+        let output_path = output_path.into();
+        let brain = self.load();
+
+        blocking(move || brain.to_file(output_path)).await
+    }
+
+    /// Generate a reponse from an input, like [`Brain::generate_with_rng`].
+    ///
+    /// This never waits, so it can be called from async code directly.
+    /// The input is not learnt, even if [`training`] is enabled:
+    /// use [`AsyncBrain::learn`] to learn it.
+    ///
+    /// [`Brain::generate_with_rng`]: crate::brain::Brain::generate_with_rng
+    /// [`training`]: crate::config::BrainConfig::training
+    /// [`AsyncBrain::learn`]: crate::async_brain::AsyncBrain::learn
+    pub fn generate(&self, input: impl AsRef<str>) -> Result<Option<String>> {
+        let brain = self.load();
+
+        with_thread_rng(|rng| brain.generate_with_rng(input, rng))
+    }
+
+    /// Learn lines into a new version of the brain, on a blocking thread.
+    ///
+    /// Each call copies the brain, so lines are best learnt in batches.
+    pub async fn learn<I>(&self, lines: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let lines: Vec<String> = lines.into_iter().map(Into::into).collect();
+
+        self.update(move |brain| {
+            for line in lines {
+                brain.ingest(&line);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Learn a dataset, line by line, into a new version of the brain,
+    /// on a blocking thread.
+    ///
+    /// See [`SnapshotBrain::retrain`].
+    ///
+    /// [`SnapshotBrain::retrain`]: crate::snapshot::SnapshotBrain::retrain
+    pub async fn retrain(&self, dataset_path: impl Into<String>) -> Result<()> {
+        let dataset_path = dataset_path.into();
+        let snapshots = self.snapshots.clone();
+
+        blocking(move || snapshots.retrain(dataset_path)).await
+    }
+
+    /// Change a copy of the current version of the brain on a blocking thread,
+    /// then replace it.
+    ///
+    /// See [`SnapshotBrain::update`].
+    ///
+    /// [`SnapshotBrain::update`]: crate::snapshot::SnapshotBrain::update
+    pub async fn update<F>(&self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut Brain) -> Result<()> + Send + 'static,
+    {
+        let snapshots = self.snapshots.clone();
+
+        blocking(move || snapshots.update(change)).await
+    }
+
+    /// Get the current version of the brain.
+    ///
+    /// See [`SnapshotBrain::load`].
+    ///
+    /// [`SnapshotBrain::load`]: crate::snapshot::SnapshotBrain::load
+    pub fn load(&self) -> Arc<Brain> {
+        self.snapshots.load()
+    }
+}
+
+// Run `f` on a blocking thread.
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    task::spawn_blocking(f).await.map_err(IoError::from)?
+}
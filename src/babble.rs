@@ -0,0 +1,78 @@
+use crate::brain::{tokenize, Brain};
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// Generate a sentence out of nowhere, without any input.
+    ///
+    /// The first word is picked among the ones the brain saw starting a sentence,
+    /// weighted by how often they did.
+    /// The reply goes through the same options as [`Brain::generate`], except
+    /// for the [`reply_rate`], as nothing is being replied to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(sentence) = brain.babble().unwrap() {
+    ///     println!("{}", sentence);
+    /// }
+    /// ```
+    ///
+    /// [`Brain::generate`]: crate::brain::Brain::generate
+    /// [`reply_rate`]: crate::config::BrainConfig::reply_rate
+    pub fn babble(&mut self) -> Result<Option<String>> {
+        self._babble(None)
+    }
+
+    /// Generate a sentence out of nowhere, built around `topic`.
+    ///
+    /// Works like [`Brain::babble`], but the sentence is built around the first
+    /// word of `topic` the brain knows.
+    /// If it doesn't know any, this is the same as [`Brain::babble`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(sentence) = brain.babble_about("weather").unwrap() {
+    ///     println!("{}", sentence);
+    /// }
+    /// ```
+    ///
+    /// [`Brain::babble`]: crate::brain::Brain::babble
+    pub fn babble_about(&mut self, topic: impl AsRef<str>) -> Result<Option<String>> {
+        let topic = tokenize(topic.as_ref())
+            .into_iter()
+            .find(|element| !self.state_with_element_vec(element).is_empty())
+            .map(|element| element.to_string());
+
+        self._babble(topic.as_deref())
+    }
+
+    fn _babble(&mut self, topic: Option<&str>) -> Result<Option<String>> {
+        if self.state_transitions.is_empty() || self.config.mute {
+            return Ok(None);
+        }
+
+        let mut rng = self.rng.clone();
+        let mut walk = Walk::new(&mut rng, self.config.sampling);
+        let reply = self.build_reply(&mut walk, topic, false);
+        self.rng = rng;
+
+        if let Some(reply) = &reply {
+            self.remember_response(reply);
+        }
+
+        Ok(reply)
+    }
+}
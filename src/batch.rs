@@ -0,0 +1,145 @@
+use rand::{Rng, SeedableRng};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// Generate a reponse from an input, using `rng` instead of the brain's own.
+    ///
+    /// Since it only needs a shared reference, it can be called from many threads
+    /// at once. As a consequence, the input is never learnt, even if [`training`]
+    /// is enabled, and the reply is not remembered for the
+    /// [`response_history_size`] check, though recent replies are still avoided.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    ///
+    /// if let Some(response) = brain.generate_with_rng("Hello there!", &mut rng).unwrap() {
+    ///     println!("{}", response);
+    /// }
+    /// ```
+    ///
+    /// [`training`]: crate::config::BrainConfig::training
+    /// [`response_history_size`]: crate::config::BrainConfig::response_history_size
+    pub fn generate_with_rng(
+        &self,
+        input: impl AsRef<str>,
+        rng: &mut impl RngCore,
+    ) -> Result<Option<String>> {
+        if self.state_transitions.is_empty() || self.config.mute {
+            return Ok(None);
+        }
+
+        // using ! bool since the config is about reply chance, not reply non chance.
+        if !rng.gen_bool(self.config.reply_rate) {
+            return Ok(None);
+        }
+
+        let mut walk = Walk::new(rng, self.config.sampling);
+
+        Ok(self.build_reply(&mut walk, Some(input.as_ref()), false))
+    }
+
+    /// Generate a reponse for each input, like [`Brain::generate_with_rng`].
+    ///
+    /// Each reply gets its own random generator, derived from `seed` and the
+    /// index of its input, so the same inputs and seed always give the same
+    /// replies, whatever the order they are built in.
+    /// With the `rayon` feature, replies are built in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let replies = brain.generate_batch(&["Hello there!", "How are you?"], 42).unwrap();
+    ///
+    /// for reply in replies.into_iter().flatten() {
+    ///     println!("{}", reply);
+    /// }
+    /// ```
+    ///
+    /// [`Brain::generate_with_rng`]: crate::brain::Brain::generate_with_rng
+    pub fn generate_batch<S>(&self, inputs: &[S], seed: u64) -> Result<Vec<Option<String>>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.run_tasks(inputs.len(), seed, |index, rng| {
+            self.generate_with_rng(&inputs[index], rng)
+        })
+    }
+
+    /// Generate up to `count` responses to the same input, like [`Brain::generate_batch`].
+    ///
+    /// This is handy to pick the best reply with [`Brain::score`].
+    /// Fewer replies are returned when some couldn't be generated.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let candidates = brain.generate_candidates("Hello there!", 16, 42).unwrap();
+    ///
+    /// let best = candidates.iter().max_by(|a, b| {
+    ///     let a = brain.score(a.as_str()).perplexity();
+    ///     let b = brain.score(b.as_str()).perplexity();
+    ///     b.total_cmp(&a)
+    /// });
+    /// ```
+    ///
+    /// [`Brain::generate_batch`]: crate::brain::Brain::generate_batch
+    /// [`Brain::score`]: crate::brain::Brain::score
+    pub fn generate_candidates(
+        &self,
+        input: impl AsRef<str> + Sync,
+        count: usize,
+        seed: u64,
+    ) -> Result<Vec<String>> {
+        let input = input.as_ref();
+
+        let replies = self.run_tasks(count, seed, |_, rng| self.generate_with_rng(input, rng))?;
+
+        Ok(replies.into_iter().flatten().collect())
+    }
+
+    // Run `count` tasks, each with a generator seeded from `seed` and its index.
+    fn run_tasks<T, F>(&self, count: usize, seed: u64, task: F) -> Result<Vec<T>>
+    where
+        T: Send,
+        F: Fn(usize, &mut ChaCha8Rng) -> Result<T> + Sync,
+    {
+        let run = |index: usize| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(index as u64);
+            task(index, &mut rng)
+        };
+
+        #[cfg(feature = "rayon")]
+        return (0..count).into_par_iter().map(run).collect();
+
+        #[cfg(not(feature = "rayon"))]
+        return (0..count).map(run).collect();
+    }
+}
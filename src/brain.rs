@@ -1,6 +1,7 @@
 use rand::{Rng, SeedableRng};
 
 use crate::brain_prelude::*;
+use crate::originality::{line_hash, shingles, words};
 
 /// The brain is the main struct of this library.
 /// It contains the hashmap that represents the markov chain,
@@ -21,7 +22,12 @@ pub struct Brain {
     /// the transitions are weighted
     pub state_transitions: HashMap<State, Transistion>,
 
-    rng: ChaCha8Rng,
+    pub(crate) memory: BrainMemory,
+
+    // The last replies of the brain, most recent last.
+    pub(crate) recent_responses: VecDeque<String>,
+
+    pub(crate) rng: ChaCha8Rng,
 }
 
 impl Default for Brain {
@@ -29,6 +35,8 @@ impl Default for Brain {
         Self {
             config: Default::default(),
             state_transitions: Default::default(),
+            memory: Default::default(),
+            recent_responses: Default::default(),
             rng: ChaCha8Rng::from_entropy(),
         }
     }
@@ -57,7 +65,7 @@ impl Brain {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
     /// let dataset_path = "path/to/your/dataset.txt";
@@ -100,7 +108,7 @@ impl Brain {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
     /// let dataset_path = "path/to/your/dataset.txt";
@@ -123,78 +131,133 @@ impl Brain {
         Ok(self)
     }
 
+    /// Learn a line, remembering where it comes from and how it was written.
+    ///
+    /// The line is learnt once, then remembered for `source` as with
+    /// [`Brain::learn_from`], and for `tag` as with [`Brain::learn_tagged`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new();
+    /// brain.config.track_provenance = true;
+    ///
+    /// brain.learn("Hello there!", Some("user#1234"), Some("obi-wan"));
+    /// ```
+    ///
+    /// [`Brain::learn_from`]: crate::brain::Brain::learn_from
+    /// [`Brain::learn_tagged`]: crate::brain::Brain::learn_tagged
+    pub fn learn(&mut self, line: impl AsRef<str>, source: Option<&str>, tag: Option<&str>) {
+        let line = line.as_ref();
+
+        self.ingest_tagged(line, tag);
+
+        if let Some(source) = source {
+            self.remember_source(line, source);
+        }
+    }
+
     // let the brain learn from a text line.
-    fn ingest(&mut self, line: &str) {
-        let line = line.to_lowercase();
+    pub(crate) fn ingest(&mut self, line: &str) {
+        self.ingest_tagged(line, None);
+    }
 
+    // Learn a text line, counting it for `tag` as well.
+    fn ingest_tagged(&mut self, line: &str, tag: Option<&str>) {
         // We get the input as str, turn it into a vec of StateElement
-        let mut split: Vec<StateElement> = line
-            .split(&SPLIT_CHARS)
-            .filter_map(|word| {
-                if word != "" {
-                    Some(StateElement::Word(word.to_string()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let split = tokenize(line);
 
-        // We add the Start and End sentence markers here, plus placeholders to be able to easily parse the vector in the window below
-        let mut elements: Vec<StateElement> = vec![
-            StateElement::Marker(SentenceMarker::Placeholder),
-            StateElement::Marker(SentenceMarker::Start),
-        ];
-        elements.append(&mut split);
-        elements.push(StateElement::Marker(SentenceMarker::End));
-        elements.push(StateElement::Marker(SentenceMarker::Placeholder));
-
-        // We constuct states from ingestion_max_state_size to 1,
-        // The more we loop, the bigger the brain will be
-        for state_size in 1..=self.config.max_ingestion_state_size {
-            if elements.len() <= state_size {
-                continue;
+        if self.config.reject_training_lines && !split.is_empty() {
+            self.memory
+                .line_hashes
+                .insert(line_hash(&sentence_to_string(&split)));
+        }
+
+        if self.config.max_copy_ratio < 1f64 {
+            let line = sentence_to_string(&split);
+
+            for shingle in shingles(&words(&line), self.config.copy_shingle_size) {
+                *self.memory.shingles.entry(shingle).or_default() += 1;
             }
+        }
 
-            elements.windows(state_size + 2).for_each(|window| {
-                let prev_element = window.first().unwrap();
-                let next_element = window.last().unwrap();
+        let sentences = self.line_sentences(split);
 
-                let constructed_state = State(window[1..state_size + 1].to_vec());
+        for pair in sentences.windows(2) {
+            if let (Some(StateElement::Word(last)), Some(StateElement::Word(first))) =
+                (pair[0].last(), pair[1].first())
+            {
+                self.memory.link_sentences(last, first);
+            }
+        }
 
-                let transition =
-                    self.state_transitions
-                        .entry(constructed_state)
-                        .or_insert(Transistion {
-                            prev: vec![],
-                            next: vec![],
-                        });
+        let max_state_size = self.config.max_ingestion_state_size;
 
-                transition.increment_occurence(SentenceDirection::Backward, prev_element);
-                transition.increment_occurence(SentenceDirection::Forward, next_element);
-            });
+        for sentence in sentences {
+            if self.config.decay_half_life > 0f64 {
+                self.see_states(sentence.clone());
+            }
+
+            learn_states(&mut self.state_transitions, sentence, max_state_size, tag);
+        }
+
+        if let Some(tag) = tag {
+            *self.memory.tags.entry(tag.to_string()).or_default() += 1;
+        }
+
+        self.limit_states();
+        self.limit_lines();
+    }
+
+    // The sentences of a tokenized line, as they are learnt.
+    pub(crate) fn line_sentences(&self, split: Vec<StateElement>) -> Vec<Vec<StateElement>> {
+        match self.config.learn_sentence_links {
+            true => split_sentences(split),
+            false => vec![split],
+        }
+    }
+
+    // Add everything another brain learnt to this one,
+    // as if this brain had learnt the same lines after its own.
+    pub(crate) fn absorb(&mut self, other: Brain) {
+        self.absorb_last_seen(&other, 1f64);
+
+        for (state, other_transition) in other.state_transitions {
+            match self.state_transitions.get_mut(&state) {
+                Some(transition) => transition.absorb(&other_transition, 1f64),
+                None => {
+                    self.state_transitions.insert(state, other_transition);
+                }
+            }
         }
+
+        self.memory.absorb(&other.memory, 1f64);
+        self.limit_states();
+        self.limit_lines();
     }
 
     /// Save the current brain to disk.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
+    /// # let brain = Brain::new();
     /// // ...
     ///
-    /// brain.to_file("brain.bin");
+    /// brain.to_file("brain.bin").unwrap();
     /// ```
     ///
     pub fn to_file(&self, output_path: impl AsRef<str>) -> Result<()> {
         let output_path = output_path.as_ref();
         println!("Saving brain...");
 
-        let serialized = bincode::serialize(&self.state_transitions).unwrap();
         let mut output_file = File::create(output_path)?;
 
-        output_file.write(&serialized)?;
+        output_file.write_all(&self.to_bytes())?;
 
         println!("Saved brain as {}", output_path);
         Ok(())
@@ -204,15 +267,14 @@ impl Brain {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
     /// let dataset_path = "path/to/your/dataset.txt";
     ///
-    /// let brain = Brain::from_file("path/to/brain.bin").unwrap()
-    ///                 .get();
+    /// let brain = Brain::from_file("path/to/brain.bin").unwrap();
     ///
-    /// brain.to_file("brain.bin");
+    /// brain.to_file("brain.bin").unwrap();
     /// ```
     ///
     pub fn from_file(brain_path: impl AsRef<str>) -> Result<Self> {
@@ -224,24 +286,57 @@ impl Brain {
 
         save_file.read_to_end(&mut buffer)?;
 
-        let state_transitions: HashMap<State, Transistion> = bincode::deserialize(&buffer).unwrap();
+        Ok(Self::from_bytes(&buffer))
+    }
+
+    // The brain as saved to disk.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&(&self.state_transitions, &self.memory)).unwrap()
+    }
+
+    // A brain from what `to_bytes` saved.
+    pub(crate) fn from_bytes(buffer: &[u8]) -> Self {
+        // Brains saved by older versions only hold the markov chain, without tags.
+        #[derive(Deserialize)]
+        struct UntaggedTransistion {
+            prev: Vec<(StateElement, usize)>,
+            next: Vec<(StateElement, usize)>,
+        }
+
+        let (state_transitions, memory): (HashMap<State, Transistion>, BrainMemory) =
+            match bincode::deserialize(buffer) {
+                Ok(saved) => saved,
+                Err(_) => {
+                    let state_transitions: HashMap<State, UntaggedTransistion> =
+                        bincode::deserialize(buffer).unwrap();
+
+                    let state_transitions = state_transitions
+                        .into_iter()
+                        .map(|(state, transition)| {
+                            let transition = Transistion {
+                                prev: transition.prev,
+                                next: transition.next,
+                                tags: vec![],
+                            };
+                            (state, transition)
+                        })
+                        .collect();
 
-        Ok(Brain {
+                    (state_transitions, Default::default())
+                }
+            };
+
+        Brain {
             state_transitions,
+            memory,
             ..Default::default()
-        })
+        }
     }
 
-    fn state_with_element_vec(&self, element: &StateElement) -> Vec<&State> {
+    pub(crate) fn state_with_element_vec(&self, element: &StateElement) -> Vec<&State> {
         self.state_transitions
-            .iter()
-            .filter_map(|(state, _)| {
-                if state.0.contains(element) {
-                    Some(state)
-                } else {
-                    None
-                }
-            })
+            .keys()
+            .filter(|state| state.0.contains(element))
             .collect()
     }
 
@@ -249,11 +344,11 @@ impl Brain {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
     /// let mut brain = Brain::new()
-    ///     .from_dataset("your_dataset.txt")
+    ///     .from_dataset("your_dataset.txt").unwrap()
     ///     .get();
     ///
     ///
@@ -265,7 +360,7 @@ impl Brain {
     /// ```
     ///
     pub fn generate(&mut self, input: impl AsRef<str>) -> Result<Option<String>> {
-        self._generate(input, false)
+        self.generate_with_sampling(input, self.config.sampling)
     }
 
     /// Generate a reponse from an input.
@@ -274,11 +369,11 @@ impl Brain {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
     /// let mut brain = Brain::new()
-    ///     .from_dataset("your_dataset.txt")
+    ///     .from_dataset("your_dataset.txt").unwrap()
     ///     .get();
     ///
     ///
@@ -291,16 +386,88 @@ impl Brain {
     /// [`reply_rate`]: crate::config::BrainConfig::reply_rate
     pub fn generate_bypass_checks(&mut self, input: impl AsRef<str>) -> Result<String> {
         // Safe to unwrap as it will always have a response.
-        match self._generate(input, true) {
-            Ok(response) => Ok(response.unwrap()),
+        match self._generate(input, true, self.config.sampling, false, None) {
+            Ok(response) => Ok(response.unwrap().reply),
             Err(e) => Err(e),
         }
     }
 
-    fn _generate(&mut self, input: impl AsRef<str>, bypass_checks: bool) -> Result<Option<String>> {
+    /// Generate a reponse from an input,
+    /// picking words with the given [`Sampling`] instead of the configured one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::{Brain, Sampling};
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let sampling = Sampling {
+    ///     greedy: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// if let Some(response) = brain.generate_with_sampling("Hello there!", sampling).unwrap() {
+    ///     println!("{}", response);
+    /// }
+    /// ```
+    ///
+    /// [`Sampling`]: crate::sampling::Sampling
+    pub fn generate_with_sampling(
+        &mut self,
+        input: impl AsRef<str>,
+        sampling: Sampling,
+    ) -> Result<Option<String>> {
+        Ok(self
+            ._generate(input, false, sampling, false, None)?
+            .map(|explained| explained.reply))
+    }
+
+    /// Generate a reponse from an input, along with how it was built.
+    ///
+    /// The trace holds the seed of each sentence, and every step of the walk:
+    /// the state used, the number of candidates and the probability of the picked element.
+    /// It can be serialized, to JSON for instance.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(explained) = brain.generate_explained("Hello there!").unwrap() {
+    ///     println!("{}", explained.reply);
+    ///
+    ///     for sentence in explained.trace.sentences.iter() {
+    ///         println!("seed: {:?}", sentence.seed_word);
+    ///
+    ///         for step in sentence.steps.iter() {
+    ///             println!("{:?} -> {} ({:.2})", step.state, step.element, step.probability);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn generate_explained(&mut self, input: impl AsRef<str>) -> Result<Option<ExplainedReply>> {
+        self._generate(input, false, self.config.sampling, true, None)
+    }
+
+    pub(crate) fn _generate(
+        &mut self,
+        input: impl AsRef<str>,
+        bypass_checks: bool,
+        sampling: Sampling,
+        explain: bool,
+        tags: Option<(&[&str], f64)>,
+    ) -> Result<Option<ExplainedReply>> {
         let input = input.as_ref();
 
-        if self.state_transitions.len() == 0 {
+        if self.state_transitions.is_empty() {
             if self.config.training {
                 self.ingest(input);
             }
@@ -325,76 +492,302 @@ impl Brain {
             return Ok(None);
         }
 
-        let mut elements: Vec<&str> = input.trim_end().split(&SPLIT_CHARS).collect();
+        let mut rng = self.rng.clone();
+        let (reply, trace) = {
+            let mut walk = match explain {
+                true => Walk::traced(&mut rng, sampling),
+                false => Walk::new(&mut rng, sampling),
+            };
+            walk.tags =
+                tags.filter(|(tags, _)| tags.iter().any(|tag| self.memory.tags.contains_key(*tag)));
+
+            let reply = self.build_reply(&mut walk, Some(input), bypass_checks);
+            (reply, walk.trace.take().unwrap_or_default())
+        };
+        self.rng = rng;
+
+        if let Some(reply) = &reply {
+            self.remember_response(reply);
+        }
+
+        if self.config.training {
+            self.ingest(input);
+        }
+
+        Ok(reply.map(|reply| ExplainedReply { reply, trace }))
+    }
+
+    // Build sentences until one is original enough and not too similar
+    // to recent replies, within the configured number of attempts.
+    //
+    // If none is, the last one is kept when `keep_last` is set.
+    // Without input, the reply starts like the sentences the brain learnt.
+    pub(crate) fn build_reply(
+        &self,
+        walk: &mut Walk,
+        input: Option<&str>,
+        keep_last: bool,
+    ) -> Option<String> {
+        let mut reply = None;
+
+        for attempt in 1..=self.config.max_generation_attempts.max(1) {
+            walk.truncate_trace(0);
+            if let Some(trace) = &mut walk.trace {
+                trace.attempts = attempt;
+            }
+
+            let candidate = sentence_to_string(&self.build_paragraph(walk, input));
+
+            if self.is_original(&candidate, input.unwrap_or_default())
+                && !self.is_recent_response(&candidate)
+            {
+                return Some(candidate);
+            }
+
+            reply = Some(candidate);
+        }
+
+        if keep_last {
+            reply
+        } else {
+            None
+        }
+    }
+
+    // Build as many sentences as the reply length asks for.
+    //
+    // Sentences after the first one are seeded from the ones the brain saw following
+    // the previous sentence, or from keywords of the previous sentence and the input.
+    fn build_paragraph(&self, walk: &mut Walk, input: Option<&str>) -> Vec<StateElement> {
+        let mut paragraph = match input {
+            Some(input) => self.build_sentence(walk, input),
+            None => {
+                let start = StateElement::Marker(SentenceMarker::Start);
+                walk.trace_seed(None, std::slice::from_ref(&start), &start);
+                self.complete_sentence(walk, vec![start])
+            }
+        };
+        let input = input.unwrap_or_default();
+        let mut sentences = 1;
+
+        loop {
+            let words = paragraph
+                .iter()
+                .filter(|element| matches!(element, StateElement::Word(_)))
+                .count();
+
+            let done = match self.config.reply_length {
+                ReplyLength::Sentences(count) => sentences >= count,
+                // Every added sentence has words, so this always ends.
+                ReplyLength::Words(count) => words >= count,
+            };
+
+            if done {
+                break;
+            }
+
+            let previous_start = paragraph
+                .iter()
+                .rposition(|element| *element == StateElement::Marker(SentenceMarker::Start))
+                .unwrap_or(0);
+
+            let mut next_sentence = None;
+
+            for _ in 0..self.config.max_generation_attempts.max(1) {
+                let traced_sentences = walk.traced_sentences();
+                let sentence = self.build_next_sentence(walk, &paragraph[previous_start..], input);
+
+                // A sentence already in the paragraph isn't worth repeating.
+                let repeated = paragraph
+                    .windows(sentence.len())
+                    .any(|window| window == sentence.as_slice());
+                let has_words = sentence
+                    .iter()
+                    .any(|element| matches!(element, StateElement::Word(_)));
+
+                if has_words && !repeated {
+                    next_sentence = Some(sentence);
+                    break;
+                }
+
+                walk.truncate_trace(traced_sentences);
+            }
+
+            match next_sentence {
+                Some(sentence) => paragraph.extend(sentence),
+                None => break,
+            }
+            sentences += 1;
+        }
 
-        let mut sentence: Vec<StateElement> = vec![];
-        let mut original_element = None;
+        paragraph
+    }
+
+    fn build_next_sentence(
+        &self,
+        walk: &mut Walk,
+        previous: &[StateElement],
+        input: &str,
+    ) -> Vec<StateElement> {
+        let last_word = previous.iter().rev().find_map(|element| match element {
+            StateElement::Word(word) => Some(word),
+            StateElement::Marker(_) => None,
+        });
+
+        let first_word = last_word
+            .and_then(|word| self.memory.sentence_links.get(word))
+            .and_then(|links| links.choose_weighted(walk.rng, |link| link.1).ok())
+            .map(|(word, _)| word.clone());
+
+        if let Some(first_word) = first_word {
+            let sentence = vec![
+                StateElement::Marker(SentenceMarker::Start),
+                StateElement::Word(first_word.clone()),
+            ];
+
+            walk.trace_seed(Some(&first_word), &sentence, &sentence[1]);
+
+            return self.complete_sentence(walk, sentence);
+        }
+
+        // Short words are rarely meaningful.
+        let previous = sentence_to_string(previous);
+        let keywords: Vec<&str> = previous
+            .split(' ')
+            .chain(input.split(&SPLIT_CHARS))
+            .filter(|word| word.chars().count() > 3)
+            .collect();
+
+        self.build_sentence(walk, &keywords.join(" "))
+    }
+
+    // Pick a seed element related to the input, then walk the chain
+    // backward to a `Start` marker and forward to an `End` marker.
+    fn build_sentence(&self, walk: &mut Walk, input: &str) -> Vec<StateElement> {
+        let seed = self.seed_element(walk, input);
+        self.complete_sentence(walk, vec![seed])
+    }
+
+    // Pick an element from a random state containing a word of the input,
+    // or from a random state if the brain doesn't know any of them.
+    pub(crate) fn seed_element(&self, walk: &mut Walk, input: &str) -> StateElement {
+        let mut elements: Vec<&str> = input.trim_end().split(&SPLIT_CHARS).collect();
 
-        elements.shuffle(&mut self.rng);
+        let mut seed = None;
 
-        let mut rng_clone = self.rng.clone();
+        elements.shuffle(walk.rng);
 
         while let Some(word) = elements.pop() {
-            let states = self.state_with_element_vec(&StateElement::Word(word.to_string()));
-            let state = match states.choose(&mut rng_clone) {
+            let element = StateElement::Word(word.to_string());
+
+            let mut states = self.state_with_element_vec(&element);
+
+            // Words the tags used are seeded from their own states.
+            if let Some((tags, weight)) = walk.tags {
+                let tagged: Vec<&State> = states
+                    .iter()
+                    .copied()
+                    .filter(|state| {
+                        let transition = &self.state_transitions[*state];
+                        tags.iter().any(|tag| transition.tag(tag).is_some())
+                    })
+                    .collect();
+
+                if weight > 0f64 && !tagged.is_empty() {
+                    states = tagged;
+                }
+            }
+            let state = match states.choose(walk.rng) {
                 Some(state) => *state,
                 None => continue,
             };
 
-            original_element = Some(state.random_element(&mut rng_clone));
+            seed = Some((Some(word), state));
             break;
         }
 
-        if let None = original_element {
-            original_element = Some(
-                self.state_transitions
-                    .keys()
-                    .choose(&mut self.rng)
-                    .unwrap()
-                    .random_element(&mut self.rng),
-            );
+        let (seed_word, seed_state) = match seed {
+            Some(seed) => seed,
+            None => (
+                None,
+                self.state_transitions.keys().choose(walk.rng).unwrap(),
+            ),
+        };
+
+        let original_element = seed_state.random_element(walk.rng);
+
+        walk.trace_seed(seed_word, &seed_state.0, original_element);
+
+        original_element.clone()
+    }
+
+    // Extend a sentence fragment until it is bounded by `Start` and `End` markers.
+    pub(crate) fn complete_sentence(
+        &self,
+        walk: &mut Walk,
+        sentence: Vec<StateElement>,
+    ) -> Vec<StateElement> {
+        let given_len = sentence.len();
+        let mut sentence = self.complete_backward(walk, sentence);
+        let given = sentence.len() - given_len..sentence.len();
+
+        while *sentence.last().unwrap() != StateElement::Marker(SentenceMarker::End) {
+            self.step_forward(walk, &mut sentence, &given);
         }
 
-        sentence.push(original_element.unwrap().clone());
+        sentence
+    }
+
+    // Extend a sentence fragment until it starts with a `Start` marker.
+    pub(crate) fn complete_backward(
+        &self,
+        walk: &mut Walk,
+        mut sentence: Vec<StateElement>,
+    ) -> Vec<StateElement> {
+        let given_len = sentence.len();
 
         while *sentence.first().unwrap() != StateElement::Marker(SentenceMarker::Start) {
-            let prev_element = self.get_element(SentenceDirection::Backward, &sentence);
-            sentence.insert(0, prev_element.clone());
-        }
+            let given = sentence.len() - given_len..sentence.len();
 
-        while *sentence.last().unwrap() != StateElement::Marker(SentenceMarker::End) {
-            let next_element = self.get_element(SentenceDirection::Forward, &sentence);
-            sentence.push(next_element.clone());
+            if self.stop_walk(SentenceDirection::Backward, &mut sentence, &given) {
+                sentence.insert(0, StateElement::Marker(SentenceMarker::Start));
+                break;
+            }
+
+            let prev_element = self.get_element(walk, SentenceDirection::Backward, &sentence);
+            sentence.insert(0, prev_element);
         }
 
-        if self.config.training {
-            self.ingest(input);
+        sentence
+    }
+
+    // Add a single element at the end of a sentence, which is an `End` marker
+    // if the walk has to stop.
+    //
+    // `given` holds the elements the walk started from, which aren't generated.
+    pub(crate) fn step_forward(
+        &self,
+        walk: &mut Walk,
+        sentence: &mut Vec<StateElement>,
+        given: &Range<usize>,
+    ) {
+        if self.stop_walk(SentenceDirection::Forward, sentence, given) {
+            sentence.push(StateElement::Marker(SentenceMarker::End));
+            return;
         }
 
-        Ok(Some(
-            sentence
-                .iter()
-                .filter_map(|element| {
-                    if let StateElement::Word(elem) = element {
-                        Some(elem.as_str())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<&str>>()
-                .join(" "),
-        ))
+        let next_element = self.get_element(walk, SentenceDirection::Forward, sentence);
+        sentence.push(next_element);
     }
 
     /// Get a [`BrainStats`] reference for the current brain.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::Brain;
     ///
-    /// let brain = Brain::load("path/to/brain.bin")
-    ///                 .get();
+    /// let brain = Brain::from_file("path/to/brain.bin").unwrap();
     ///
     /// let stats = brain.stats();
     ///
@@ -402,54 +795,493 @@ impl Brain {
     /// ```
     ///
     /// [`BrainStats`]: crate::stats::BrainStats
-    pub fn stats(&self) -> BrainStats {
+    pub fn stats(&self) -> BrainStats<'_> {
         BrainStats::new(self)
     }
 
-    fn get_element(
-        &mut self,
+    // Look up the transitions of the sentence edge facing `direction`,
+    // for every state size of the generation range, longest first.
+    //
+    // With `tags`, only the occurences learnt with any of them are counted.
+    // Transitions without any element in this direction are skipped.
+    fn find_transitions(
+        &self,
         direction: SentenceDirection,
         sentence: &[StateElement],
-    ) -> &StateElement {
-        let mut transition = None;
+        tags: Option<&[&str]>,
+    ) -> Vec<StateOccurences<'_>> {
+        let mut transitions = vec![];
+        let mut last_size = None;
 
-        for state_size in self.config.get_state_range() {
+        for state_size in self.config.get_state_range().rev() {
             let min = state_size.min(sentence.len());
-            match direction {
-                SentenceDirection::Backward => {
-                    transition = self
+
+            if min == 0 || last_size == Some(min) {
+                continue;
+            }
+            last_size = Some(min);
+
+            let state = match direction {
+                SentenceDirection::Backward => &sentence[0..min],
+                SentenceDirection::Forward => &sentence[sentence.len() - min..sentence.len()],
+            };
+
+            if let Some(transition) = self.state_transitions.get(state) {
+                if !transition.elements(direction).is_empty() {
+                    let mut elements = self.decayed_occurences(direction, state, transition);
+
+                    if let Some(tags) = tags {
+                        elements = tagged_occurences(direction, transition, elements, tags);
+                    }
+
+                    let total: f64 = elements.iter().map(|(_, occurence)| occurence).sum();
+
+                    // Occurences seen long enough ago may decay to nothing.
+                    if total <= 0f64 {
+                        continue;
+                    }
+
+                    transitions.push(StateOccurences {
+                        order: min,
+                        elements,
+                        total,
+                    });
+                }
+            }
+        }
+
+        transitions
+    }
+
+    // The elements that can come next in `direction`, with their probability
+    // according to the configured backoff.
+    pub(crate) fn candidates(
+        &self,
+        direction: SentenceDirection,
+        sentence: &[StateElement],
+    ) -> Candidates<'_> {
+        self.candidates_in(direction, sentence, None)
+    }
+
+    // The candidates according to the lines learnt with any of `tags`,
+    // or every line without tags.
+    pub(crate) fn candidates_in(
+        &self,
+        direction: SentenceDirection,
+        sentence: &[StateElement],
+        tags: Option<&[&str]>,
+    ) -> Candidates<'_> {
+        let transitions = self.find_transitions(direction, sentence, tags);
+
+        let longest = match transitions.first() {
+            Some(transition) => transition,
+            None => {
+                return Candidates {
+                    elements: vec![],
+                    occurences: 0,
+                    order: 0,
+                }
+            }
+        };
+
+        let order = longest.order;
+        let occurences = longest.total.round() as usize;
+
+        let elements = match &self.config.backoff {
+            Backoff::LongestMatch => longest
+                .elements
+                .iter()
+                .map(|(element, occurence)| (*element, occurence / longest.total))
+                .collect(),
+            Backoff::Interpolated(weights) => {
+                let weight = |state_size: usize| {
+                    state_size
+                        .checked_sub(self.config.min_generation_state_size)
+                        .and_then(|index| weights.get(index))
+                        .copied()
+                        .unwrap_or(1f64)
+                };
+
+                let total_weight: f64 = transitions
+                    .iter()
+                    .map(|transition| weight(transition.order))
+                    .sum();
+
+                let mut elements = shortest_elements(&transitions);
+
+                for transition in transitions.iter() {
+                    let weight = weight(transition.order) / total_weight;
+
+                    for_each_probability(
+                        transition,
+                        &mut elements,
+                        |probability, occurence, total| *probability += weight * occurence / total,
+                    );
+                }
+
+                elements
+            }
+            Backoff::KneserNey(discount) => {
+                let discount = discount.clamp(0f64, 1f64);
+                let mut elements = shortest_elements(&transitions);
+
+                // Elements following many different elements get more of the mass
+                // left by the shortest state.
+                for (element, probability) in elements.iter_mut() {
+                    *probability = self
                         .state_transitions
-                        .get(&State((&sentence[0..min]).to_vec()))
+                        .get(std::slice::from_ref(*element))
+                        .map_or(0, |transition| {
+                            transition.elements(direction.reverse()).len()
+                        })
+                        .max(1) as f64;
+                }
+
+                let total: f64 = elements.iter().map(|(_, continuation)| continuation).sum();
+                elements
+                    .iter_mut()
+                    .for_each(|(_, continuation)| *continuation /= total);
+
+                for transition in transitions.iter().rev() {
+                    // Decayed occurences may be lower than the discount.
+                    let left_mass = transition
+                        .elements
+                        .iter()
+                        .map(|(_, occurence)| discount.min(*occurence))
+                        .sum::<f64>()
+                        / transition.total;
+
+                    elements
+                        .iter_mut()
+                        .for_each(|(_, probability)| *probability *= left_mass);
+
+                    for_each_probability(
+                        transition,
+                        &mut elements,
+                        |probability, occurence, total| {
+                            *probability += (occurence - discount).max(0f64) / total
+                        },
+                    );
                 }
+
+                elements
+            }
+        };
+
+        Candidates {
+            elements,
+            occurences,
+            order,
+        }
+    }
+
+    pub(crate) fn get_element(
+        &self,
+        walk: &mut Walk,
+        direction: SentenceDirection,
+        sentence: &[StateElement],
+    ) -> StateElement {
+        let end_marker = match direction {
+            SentenceDirection::Backward => &StateElement::Marker(SentenceMarker::Start),
+            SentenceDirection::Forward => &StateElement::Marker(SentenceMarker::End),
+        };
+
+        let mut candidates = self.candidates(direction, sentence);
+
+        // A pruned brain may not know any state of the generation range here,
+        // but it always knows the element at the edge on its own.
+        if candidates.elements.is_empty() && sentence.len() > 1 {
+            candidates = match direction {
+                SentenceDirection::Backward => self.candidates(direction, &sentence[..1]),
                 SentenceDirection::Forward => {
-                    transition = self.state_transitions.get(&State(
-                        (&sentence[sentence.len() - min..sentence.len()]).to_vec(),
-                    ))
+                    self.candidates(direction, &sentence[sentence.len() - 1..])
+                }
+            };
+        }
+
+        if let Some((tags, weight)) = walk.tags {
+            let tag_candidates = self.candidates_in(direction, sentence, Some(tags));
+            candidates.blend(tag_candidates, weight);
+        }
+
+        let probabilities = walk.trace.as_ref().map(|_| candidates.elements.clone());
+
+        self.penalize_repetitions(direction, sentence, &mut candidates.elements);
+
+        let element = walk
+            .sampling
+            .sample(walk.rng, &candidates.elements)
+            .unwrap_or(end_marker);
+
+        if let Some(probabilities) = probabilities {
+            let order = candidates.order;
+
+            walk.trace_step(|| TraceStep {
+                direction,
+                state: match direction {
+                    SentenceDirection::Backward => &sentence[..order],
+                    SentenceDirection::Forward => &sentence[sentence.len() - order..],
                 }
+                .iter()
+                .map(|element| element.to_string())
+                .collect(),
+                order,
+                candidates: probabilities.len(),
+                probability: probabilities
+                    .iter()
+                    .find(|(candidate, _)| *candidate == element)
+                    .map_or(0f64, |(_, probability)| *probability),
+                element: element.to_string(),
+            });
+        }
+
+        element.clone()
+    }
+}
+
+// The occurences of the elements following a state in a direction,
+// decayed when weights decay.
+struct StateOccurences<'a> {
+    order: usize,
+    elements: Vec<(&'a StateElement, f64)>,
+    total: f64,
+}
+
+// The occurences of the elements of a transition learnt with any of `tags`,
+// decayed as much as all of their occurences.
+fn tagged_occurences<'a>(
+    direction: SentenceDirection,
+    transition: &Transistion,
+    elements: Vec<(&'a StateElement, f64)>,
+    tags: &[&str],
+) -> Vec<(&'a StateElement, f64)> {
+    elements
+        .into_iter()
+        .filter_map(|(element, occurence)| {
+            let tagged: usize = tags
+                .iter()
+                .filter_map(|tag| transition.tag(tag))
+                .map(|tagged| tagged.count(direction, element))
+                .sum();
+
+            let share = tagged as f64 / transition.count(direction, element) as f64;
+            (tagged > 0).then_some((element, occurence * share))
+        })
+        .collect()
+}
+
+// Learn the states of the words of a single sentence into a markov chain,
+// counting them for `tag` as well.
+fn learn_states(
+    state_transitions: &mut HashMap<State, Transistion>,
+    split: Vec<StateElement>,
+    max_state_size: usize,
+    tag: Option<&str>,
+) {
+    for_each_window(
+        split,
+        max_state_size,
+        |state, prev_element, next_element| {
+            // Only build the state when it's new.
+            if !state_transitions.contains_key(state) {
+                state_transitions.insert(State(state.to_vec()), Default::default());
             }
 
-            if let None = transition {
-                continue;
+            let transition = state_transitions.get_mut(state).unwrap();
+
+            transition.increment_occurence(SentenceDirection::Backward, prev_element);
+            transition.increment_occurence(SentenceDirection::Forward, next_element);
+
+            if let Some(tag) = tag {
+                let tagged = transition.tag_mut(tag);
+
+                tagged.increment_occurence(SentenceDirection::Backward, prev_element);
+                tagged.increment_occurence(SentenceDirection::Forward, next_element);
             }
+        },
+    );
+}
 
-            break;
+// Call `f` with every state of a sentence, along with the elements before and after it.
+//
+// States spanning the whole sentence are left out, as nothing comes before or after them.
+pub(crate) fn for_each_window(
+    mut split: Vec<StateElement>,
+    max_state_size: usize,
+    mut f: impl FnMut(&[StateElement], &StateElement, &StateElement),
+) {
+    // We add the Start and End sentence markers here, plus placeholders to be able to easily parse the vector in the window below
+    let mut elements: Vec<StateElement> = vec![
+        StateElement::Marker(SentenceMarker::Placeholder),
+        StateElement::Marker(SentenceMarker::Start),
+    ];
+    elements.append(&mut split);
+    elements.push(StateElement::Marker(SentenceMarker::End));
+    elements.push(StateElement::Marker(SentenceMarker::Placeholder));
+
+    // We constuct states from ingestion_max_state_size to 1,
+    // The more we loop, the bigger the brain will be
+    for state_size in 1..=max_state_size {
+        if elements.len() <= state_size {
+            continue;
         }
 
-        if let None = transition {
-            return match direction {
-                SentenceDirection::Backward => &StateElement::Marker(SentenceMarker::Start),
-                SentenceDirection::Forward => &StateElement::Marker(SentenceMarker::End),
-            };
+        elements.windows(state_size + 2).for_each(|window| {
+            let prev_element = window.first().unwrap();
+            let next_element = window.last().unwrap();
+
+            if *prev_element == StateElement::Marker(SentenceMarker::Placeholder)
+                && *next_element == StateElement::Marker(SentenceMarker::Placeholder)
+            {
+                return;
+            }
+
+            f(&window[1..state_size + 1], prev_element, next_element);
+        });
+    }
+}
+
+// Every element known to the shortest of the transitions, with a probability of 0.
+//
+// The shortest state knows every element the longer ones know.
+fn shortest_elements<'a>(transitions: &[StateOccurences<'a>]) -> Vec<(&'a StateElement, f64)> {
+    let shortest = transitions.last().unwrap();
+
+    shortest
+        .elements
+        .iter()
+        .map(|(element, _)| (*element, 0f64))
+        .collect()
+}
+
+// Update the probability of every element known to a state,
+// given its occurence and the state total.
+fn for_each_probability(
+    transition: &StateOccurences,
+    elements: &mut [(&StateElement, f64)],
+    mut update: impl FnMut(&mut f64, f64, f64),
+) {
+    let occurences: HashMap<&StateElement, f64> = transition.elements.iter().copied().collect();
+
+    for (element, probability) in elements.iter_mut() {
+        let occurence = occurences.get(element).copied().unwrap_or(0f64);
+        update(probability, occurence, transition.total);
+    }
+}
+
+// Join the words of a sentence, leaving its markers out.
+//
+// When it holds several sentences, they are ended with a period
+// if they don't already end with a punctuation mark.
+pub(crate) fn sentence_to_string(sentence: &[StateElement]) -> String {
+    let mut text = String::new();
+    let mut sentence_ended = false;
+
+    for element in sentence.iter() {
+        match element {
+            StateElement::Word(word) => {
+                if !text.is_empty() {
+                    if sentence_ended && !text.ends_with(SENTENCE_END_CHARS) {
+                        text.push('.');
+                    }
+                    text.push(' ');
+                }
+
+                text.push_str(word);
+                sentence_ended = false;
+            }
+            StateElement::Marker(SentenceMarker::End) => sentence_ended = true,
+            StateElement::Marker(_) => {}
         }
+    }
 
-        let attribute = match direction {
-            SentenceDirection::Backward => &transition.unwrap().prev,
-            SentenceDirection::Forward => &transition.unwrap().next,
+    text
+}
+
+// Split words into sentences, after each word ending with a punctuation mark.
+fn split_sentences(split: Vec<StateElement>) -> Vec<Vec<StateElement>> {
+    let mut sentences = vec![vec![]];
+
+    for element in split {
+        let ends_sentence = match &element {
+            StateElement::Word(word) => word.ends_with(SENTENCE_END_CHARS),
+            StateElement::Marker(_) => false,
         };
 
-        &attribute
-            .choose_weighted(&mut self.rng, |item| item.1)
+        sentences.last_mut().unwrap().push(element);
+
+        if ends_sentence {
+            sentences.push(vec![]);
+        }
+    }
+
+    if sentences.len() > 1 && sentences.last().unwrap().is_empty() {
+        sentences.pop();
+    }
+
+    sentences
+}
+
+// Turn a text line into the words the brain works with.
+pub(crate) fn tokenize(line: &str) -> Vec<StateElement> {
+    line.to_lowercase()
+        .split(&SPLIT_CHARS)
+        .filter_map(|word| {
+            if !word.is_empty() {
+                Some(StateElement::Word(word.to_string()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::SentenceDirection;
+    use crate::prelude::*;
+    use crate::test_utils::dataset;
+
+    // Whether the probabilities of the candidates following every state sum to 1.
+    fn assert_candidates_sum_to_one(backoff: Backoff) {
+        let mut brain = Brain::new()
+            .config(BrainConfig {
+                backoff,
+                ..Default::default()
+            })
             .unwrap()
-            .0
+            .get();
+
+        for line in dataset(500) {
+            brain.ingest(&line);
+        }
+
+        for state in brain.state_transitions.keys() {
+            for direction in [SentenceDirection::Backward, SentenceDirection::Forward] {
+                let candidates = brain.candidates(direction, &state.0);
+
+                if candidates.elements.is_empty() {
+                    continue;
+                }
+
+                let total: f64 = candidates
+                    .elements
+                    .iter()
+                    .map(|(_, probability)| probability)
+                    .sum();
+
+                assert!((total - 1f64).abs() < 1e-9, "{:?} sums to {}", state, total);
+            }
+        }
+    }
+
+    #[test]
+    fn interpolated_probabilities_sum_to_one() {
+        assert_candidates_sum_to_one(Backoff::Interpolated(vec![0.5, 1f64, 2f64]));
+    }
+
+    #[test]
+    fn kneser_ney_probabilities_sum_to_one() {
+        assert_candidates_sum_to_one(Backoff::KneserNey(0.75));
     }
 }
@@ -1,23 +1,254 @@
+use std::borrow::Borrow;
+
 use crate::brain_prelude::*;
+use crate::decay::decay_factor;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct State(pub(crate) Vec<StateElement>);
+
+// A state hashes and compares like its elements, so the markov chain
+// can be looked up with a slice of a sentence, without building a state.
+impl Borrow<[StateElement]> for State {
+    fn borrow(&self) -> &[StateElement] {
+        &self.0
+    }
+}
 impl State {
     pub(crate) fn random_element(&self, rng: &mut dyn RngCore) -> &StateElement {
         self.0.choose(rng).unwrap()
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// What the brain remembers of its training, besides the markov chain.
+//
+// It is saved to disk along with the markov chain.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct BrainMemory {
+    // Hashes of the lines the brain learnt from, while rejecting them as replies.
+    pub(crate) line_hashes: HashSet<u64>,
+
+    // How many times each run of words was seen in the lines the brain learnt from,
+    // by hash, while checking how much replies copy them.
+    pub(crate) shingles: HashMap<u64, usize>,
+
+    // For the last word of a sentence, the first words of the sentences
+    // seen following it, weighted.
+    pub(crate) sentence_links: HashMap<String, Vec<(String, usize)>>,
+
+    // The lines learnt from each source, when provenance is tracked,
+    // with the weight they were merged with.
+    pub(crate) sources: HashMap<String, Vec<(String, f64)>>,
+
+    // For each tag, how many of the lines the brain holds were learnt with it.
+    pub(crate) tags: HashMap<String, usize>,
+
+    // The current epoch, for transition weights to decay over time.
+    pub(crate) epoch: u64,
+
+    // When the elements around each state were last seen, and how many times
+    // they had been seen by then, decayed, when weights decay.
+    pub(crate) last_seen: HashMap<State, LastSeen>,
+}
+impl BrainMemory {
+    pub(crate) fn link_sentences(&mut self, last_word: &str, first_word: &str) {
+        let links = self
+            .sentence_links
+            .entry(last_word.to_string())
+            .or_default();
+
+        match links.iter_mut().find(|(word, _)| word == first_word) {
+            Some((_, occurence)) => *occurence += 1,
+            None => links.push((first_word.to_string(), 1)),
+        }
+    }
+
+    // Add what another brain remembers to this memory,
+    // its link counts multiplied by `weight`.
+    pub(crate) fn absorb(&mut self, other: &BrainMemory, weight: f64) {
+        self.line_hashes.extend(&other.line_hashes);
+        self.epoch = self.epoch.max(other.epoch);
+
+        for (source, lines) in &other.sources {
+            self.sources.entry(source.clone()).or_default().extend(
+                lines
+                    .iter()
+                    .map(|(line, line_weight)| (line.clone(), line_weight * weight))
+                    .filter(|(_, line_weight)| *line_weight > 0f64),
+            );
+        }
+
+        for (tag, lines) in &other.tags {
+            *self.tags.entry(tag.clone()).or_default() += lines;
+        }
+
+        for (shingle, count) in &other.shingles {
+            *self.shingles.entry(*shingle).or_default() += count;
+        }
+
+        for (last_word, other_links) in &other.sentence_links {
+            for (first_word, count) in other_links {
+                let count = scale_count(*count, weight);
+
+                if count == 0 {
+                    continue;
+                }
+
+                let links = self.sentence_links.entry(last_word.clone()).or_default();
+
+                match links.iter_mut().find(|(word, _)| word == first_word) {
+                    Some((_, occurence)) => *occurence += count,
+                    None => links.push((first_word.clone(), count)),
+                }
+            }
+        }
+    }
+
+    // Lower every link count to what multiplying it by `weight` gives, rounded down.
+    pub(crate) fn scale_down(&mut self, weight: f64) {
+        for links in self.sentence_links.values_mut() {
+            scale_down_occurences(links, weight);
+        }
+
+        self.sentence_links.retain(|_, links| !links.is_empty());
+    }
+
+    // Whether this memory holds at least every link of another one.
+    pub(crate) fn holds(&self, other: &BrainMemory) -> bool {
+        other.sentence_links.iter().all(|(last_word, other_links)| {
+            self.sentence_links
+                .get(last_word)
+                .is_some_and(|links| holds_occurences(links, other_links))
+        })
+    }
+
+    // Remove what another memory holds from this one, as long as it `holds` it.
+    //
+    // Line hashes are left alone, as the lines may still be held several times.
+    // Runs of words are counted out as far as they are held.
+    pub(crate) fn subtract(&mut self, other: &BrainMemory) {
+        for (shingle, other_count) in &other.shingles {
+            if let Some(count) = self.shingles.get_mut(shingle) {
+                *count = count.saturating_sub(*other_count);
+
+                if *count == 0 {
+                    self.shingles.remove(shingle);
+                }
+            }
+        }
+
+        for (last_word, other_links) in &other.sentence_links {
+            if let Some(links) = self.sentence_links.get_mut(last_word) {
+                subtract_occurences(links, other_links);
+
+                if links.is_empty() {
+                    self.sentence_links.remove(last_word);
+                }
+            }
+        }
+    }
+
+    // Forget every link and epoch involving `word`.
+    pub(crate) fn forget_word(&mut self, word: &str) {
+        self.sentence_links.remove(word);
+
+        for links in self.sentence_links.values_mut() {
+            links.retain(|(first_word, _)| first_word != word);
+        }
+
+        self.sentence_links.retain(|_, links| !links.is_empty());
+
+        let element = StateElement::Word(word.to_string());
+
+        self.last_seen.retain(|state, last_seen| {
+            last_seen.prev.remove(&element);
+            last_seen.next.remove(&element);
+            !state.0.contains(&element)
+        });
+    }
+}
+
+// When the elements around a state were last seen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LastSeen {
+    pub(crate) prev: HashMap<StateElement, Seen>,
+    pub(crate) next: HashMap<StateElement, Seen>,
+}
+impl LastSeen {
+    pub(crate) fn seen(
+        &self,
+        direction: SentenceDirection,
+        element: &StateElement,
+    ) -> Option<&Seen> {
+        match direction {
+            SentenceDirection::Backward => self.prev.get(element),
+            SentenceDirection::Forward => self.next.get(element),
+        }
+    }
+
+    pub(crate) fn set(&mut self, direction: SentenceDirection, element: &StateElement, seen: Seen) {
+        let elements = match direction {
+            SentenceDirection::Backward => &mut self.prev,
+            SentenceDirection::Forward => &mut self.next,
+        };
+
+        match elements.get_mut(element) {
+            Some(last_seen) => *last_seen = seen,
+            None => {
+                elements.insert(element.clone(), seen);
+            }
+        }
+    }
+
+    // Forget the elements a transition doesn't hold anymore.
+    pub(crate) fn forget_missing(&mut self, transition: &Transistion) {
+        for (seen, occurences) in [
+            (&mut self.prev, &transition.prev),
+            (&mut self.next, &transition.next),
+        ] {
+            let elements: HashSet<&StateElement> =
+                occurences.iter().map(|(element, _)| element).collect();
+
+            seen.retain(|element, _| elements.contains(element));
+        }
+    }
+}
+
+// The epoch an element was last seen, and how many times it had been seen by then,
+// each occurence decayed according to how long before it was.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct Seen {
+    pub(crate) epoch: u64,
+    pub(crate) occurences: f64,
+}
+impl Seen {
+    // The occurences, decayed further until `epoch`.
+    pub(crate) fn decayed(&self, epoch: u64, half_life: f64) -> f64 {
+        self.occurences * decay_factor(epoch.saturating_sub(self.epoch), half_life)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transistion {
     pub(crate) prev: Vec<(StateElement, usize)>,
     pub(crate) next: Vec<(StateElement, usize)>,
+
+    // The part of these occurences learnt with each tag.
+    pub(crate) tags: Vec<(String, Transistion)>,
 }
 impl Transistion {
     pub(crate) fn increment_occurence(
         &mut self,
         direction: SentenceDirection,
         new_element: &StateElement,
+    ) {
+        self.add_occurences(direction, new_element, 1);
+    }
+
+    pub(crate) fn add_occurences(
+        &mut self,
+        direction: SentenceDirection,
+        new_element: &StateElement,
+        count: usize,
     ) {
         if *new_element == StateElement::Marker(SentenceMarker::Placeholder) {
             return;
@@ -33,12 +264,260 @@ impl Transistion {
             if element != new_element {
                 continue;
             }
-            *occurence += 1;
+            *occurence += count;
             exists = true;
         }
 
         if !exists {
-            working_vec.push((new_element.clone(), 1));
+            working_vec.push((new_element.clone(), count));
+        }
+    }
+
+    // The occurences learnt with `tag`.
+    pub(crate) fn tag(&self, tag: &str) -> Option<&Transistion> {
+        self.tags
+            .iter()
+            .find(|(known, _)| known == tag)
+            .map(|(_, tagged)| tagged)
+    }
+
+    pub(crate) fn tag_mut(&mut self, tag: &str) -> &mut Transistion {
+        let index = match self.tags.iter().position(|(known, _)| known == tag) {
+            Some(index) => index,
+            None => {
+                self.tags.push((tag.to_string(), Default::default()));
+                self.tags.len() - 1
+            }
+        };
+
+        &mut self.tags[index].1
+    }
+
+    // Add the occurences of another transition to this one, tags included,
+    // multiplied by `weight`.
+    pub(crate) fn absorb(&mut self, other: &Transistion, weight: f64) {
+        for direction in [SentenceDirection::Backward, SentenceDirection::Forward] {
+            for (element, count) in other.elements(direction) {
+                let count = scale_count(*count, weight);
+
+                if count > 0 {
+                    self.add_occurences(direction, element, count);
+                }
+            }
+        }
+
+        for (tag, other_tagged) in other.tags.iter() {
+            self.tag_mut(tag).absorb(other_tagged, weight);
+        }
+
+        self.tags.retain(|(_, tagged)| !tagged.is_empty());
+    }
+
+    // Lower every occurence to what multiplying it by `weight` gives, rounded down.
+    pub(crate) fn scale_down(&mut self, weight: f64) {
+        scale_down_occurences(&mut self.prev, weight);
+        scale_down_occurences(&mut self.next, weight);
+        self.bound_tags();
+    }
+
+    // How many times `element` was seen in `direction`.
+    pub(crate) fn count(&self, direction: SentenceDirection, element: &StateElement) -> usize {
+        self.elements(direction)
+            .iter()
+            .find(|(known, _)| known == element)
+            .map_or(0, |(_, occurence)| *occurence)
+    }
+
+    // How many times the state was seen.
+    pub(crate) fn occurences(&self) -> usize {
+        self.total(SentenceDirection::Backward)
+            .max(self.total(SentenceDirection::Forward))
+    }
+
+    // Remove the elements seen less than `min_count` times.
+    pub(crate) fn forget_rare(&mut self, min_count: usize) {
+        self.prev.retain(|(_, occurence)| *occurence >= min_count);
+        self.next.retain(|(_, occurence)| *occurence >= min_count);
+        self.bound_tags();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.prev.is_empty() && self.next.is_empty()
+    }
+
+    // Whether this transition has at least every occurence of another one.
+    pub(crate) fn holds(&self, other: &Transistion) -> bool {
+        holds_occurences(&self.prev, &other.prev) && holds_occurences(&self.next, &other.next)
+    }
+
+    // Whether the occurences learnt without any tag are at least
+    // every occurence of another transition.
+    pub(crate) fn untagged_holds(&self, other: &Transistion) -> bool {
+        [SentenceDirection::Backward, SentenceDirection::Forward]
+            .into_iter()
+            .all(|direction| {
+                other
+                    .elements(direction)
+                    .iter()
+                    .all(|(element, other_count)| {
+                        let tagged: usize = self
+                            .tags
+                            .iter()
+                            .map(|(_, tagged)| tagged.count(direction, element))
+                            .sum();
+
+                        self.count(direction, element).saturating_sub(tagged) >= *other_count
+                    })
+            })
+    }
+
+    // Remove the occurences of another transition from this one,
+    // as long as it `holds` them, counting them out of `tag` if they were learnt with it.
+    pub(crate) fn subtract(&mut self, other: &Transistion, tag: Option<&str>) {
+        subtract_occurences(&mut self.prev, &other.prev);
+        subtract_occurences(&mut self.next, &other.next);
+
+        if let Some((_, tagged)) = self
+            .tags
+            .iter_mut()
+            .find(|(known, _)| Some(known.as_str()) == tag)
+        {
+            tagged.subtract(other, None);
         }
+
+        self.bound_tags();
+    }
+
+    // Remove every occurence of `elements`.
+    pub(crate) fn forget_elements(&mut self, elements: &HashSet<StateElement>) {
+        self.prev.retain(|(prev, _)| !elements.contains(prev));
+        self.next.retain(|(next, _)| !elements.contains(next));
+        self.bound_tags();
+    }
+
+    // Keep the occurences of each tag within the ones of the transition,
+    // as they are a part of them, and forget tags left without any.
+    pub(crate) fn bound_tags(&mut self) {
+        for (_, tagged) in self.tags.iter_mut() {
+            for (tagged, occurences) in [
+                (&mut tagged.prev, &self.prev),
+                (&mut tagged.next, &self.next),
+            ] {
+                for (element, count) in tagged.iter_mut() {
+                    let occurence = occurences
+                        .iter()
+                        .find(|(known, _)| known == element)
+                        .map_or(0, |(_, occurence)| *occurence);
+
+                    *count = (*count).min(occurence);
+                }
+
+                tagged.retain(|(_, count)| *count > 0);
+            }
+        }
+
+        self.tags.retain(|(_, tagged)| !tagged.is_empty());
+    }
+
+    pub(crate) fn elements(&self, direction: SentenceDirection) -> &[(StateElement, usize)] {
+        match direction {
+            SentenceDirection::Backward => &self.prev,
+            SentenceDirection::Forward => &self.next,
+        }
+    }
+
+    pub(crate) fn total(&self, direction: SentenceDirection) -> usize {
+        self.elements(direction)
+            .iter()
+            .map(|(_, occurence)| occurence)
+            .sum()
+    }
+}
+
+// An occurence count multiplied by a weight, rounded.
+//
+// A weight of 1 keeps the count as is.
+fn scale_count(count: usize, weight: f64) -> usize {
+    (count as f64 * weight).round() as usize
+}
+
+// Multiply the counts of `occurences` by `weight`, rounded down,
+// dropping the elements left with none.
+fn scale_down_occurences<T>(occurences: &mut Vec<(T, usize)>, weight: f64) {
+    for (_, count) in occurences.iter_mut() {
+        *count = (*count as f64 * weight).floor() as usize;
+    }
+
+    occurences.retain(|(_, count)| *count > 0);
+}
+
+// Whether `occurences` has at least the count of every element in `other`.
+fn holds_occurences<T: PartialEq>(occurences: &[(T, usize)], other: &[(T, usize)]) -> bool {
+    other.iter().all(|(other_element, other_count)| {
+        occurences
+            .iter()
+            .any(|(element, count)| element == other_element && count >= other_count)
+    })
+}
+
+// Remove the counts of `other` from `occurences`, dropping the elements left with none.
+fn subtract_occurences<T: PartialEq>(occurences: &mut Vec<(T, usize)>, other: &[(T, usize)]) {
+    for (other_element, other_count) in other {
+        if let Some((_, count)) = occurences
+            .iter_mut()
+            .find(|(element, _)| element == other_element)
+        {
+            *count = count.saturating_sub(*other_count);
+        }
+    }
+
+    occurences.retain(|(_, count)| *count > 0);
+}
+
+// The elements that can follow a state in a direction, with their probability.
+pub(crate) struct Candidates<'a> {
+    pub(crate) elements: Vec<(&'a StateElement, f64)>,
+
+    // How many occurences the probabilities are based on.
+    pub(crate) occurences: usize,
+
+    // The length of the longest state used.
+    pub(crate) order: usize,
+}
+impl<'a> Candidates<'a> {
+    // Mix in the candidates of another markov chain, weighted by `weight`
+    // between 0 and 1.
+    //
+    // Nothing changes if the other chain has no candidate.
+    pub(crate) fn blend(&mut self, other: Candidates<'a>, weight: f64) {
+        if other.elements.is_empty() {
+            return;
+        }
+
+        let weight = weight.clamp(0f64, 1f64);
+
+        for (_, probability) in self.elements.iter_mut() {
+            *probability *= 1f64 - weight;
+        }
+
+        for (other_element, other_probability) in other.elements {
+            match self
+                .elements
+                .iter_mut()
+                .find(|(element, _)| *element == other_element)
+            {
+                Some((_, probability)) => *probability += weight * other_probability,
+                None => self
+                    .elements
+                    .push((other_element, weight * other_probability)),
+            }
+        }
+    }
+
+    pub(crate) fn probability(&self, element: &StateElement) -> f64 {
+        self.elements
+            .iter()
+            .find(|(candidate, _)| *candidate == element)
+            .map_or(0f64, |(_, probability)| *probability)
     }
 }
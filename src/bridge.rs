@@ -0,0 +1,140 @@
+use crate::brain::{sentence_to_string, Brain};
+use crate::brain_prelude::*;
+
+// A partial sentence explored while searching for a bridge,
+// with the log-probability of the transitions taken to build it.
+struct BridgePath {
+    elements: Vec<StateElement>,
+    log_prob: f64,
+}
+
+impl BridgePath {
+    fn reaches(&self, from: &StateElement, to: &StateElement) -> bool {
+        match self.elements.iter().position(|element| element == from) {
+            Some(position) => self.elements[position..].contains(to),
+            None => false,
+        }
+    }
+}
+
+impl Brain {
+    /// Generate a sentence that goes from a word to another one.
+    ///
+    /// The brain searches the forward transitions for a path from a state
+    /// containing `from` to a state containing `to`, adding at most
+    /// `max_depth` words on the way. The path is then completed into a
+    /// full sentence.
+    ///
+    /// The search keeps the [`bridge_beam_width`] most likely paths at each step.
+    /// It returns `None` if no path was found within `max_depth` words, or if
+    /// the brain doesn't know `from`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(response) = brain.bridge("cats", "dogs", 10).unwrap() {
+    ///     println!("{}", response);
+    /// }
+    /// ```
+    ///
+    /// [`bridge_beam_width`]: crate::config::BrainConfig::bridge_beam_width
+    pub fn bridge(
+        &mut self,
+        from: impl AsRef<str>,
+        to: impl AsRef<str>,
+        max_depth: usize,
+    ) -> Result<Option<String>> {
+        let from = StateElement::Word(from.as_ref().to_lowercase());
+        let to = StateElement::Word(to.as_ref().to_lowercase());
+
+        let mut rng = self.rng.clone();
+        let mut walk = Walk::new(&mut rng, self.config.sampling);
+        let sentence = self
+            .find_bridge(walk.rng, &from, &to, max_depth)
+            .map(|path| self.complete_sentence(&mut walk, path));
+        self.rng = rng;
+
+        Ok(sentence.map(|sentence| sentence_to_string(&sentence)))
+    }
+
+    fn find_bridge(
+        &self,
+        rng: &mut dyn RngCore,
+        from: &StateElement,
+        to: &StateElement,
+        max_depth: usize,
+    ) -> Option<Vec<StateElement>> {
+        let beam_width = self.config.bridge_beam_width.max(1);
+
+        let mut beam: Vec<BridgePath> = self
+            .state_with_element_vec(from)
+            .into_iter()
+            .map(|state| BridgePath {
+                elements: state.0.clone(),
+                log_prob: 0f64,
+            })
+            .collect();
+
+        beam.shuffle(rng);
+        beam.truncate(beam_width);
+
+        for depth in 0..=max_depth {
+            // Every path of the beam added as many words to its starting state,
+            // so the first ones reaching `to` add the fewest words,
+            // though their starting states may not be as long.
+            let found = beam
+                .iter()
+                .filter(|path| path.reaches(from, to))
+                .max_by(|a, b| a.log_prob.total_cmp(&b.log_prob));
+
+            if let Some(path) = found {
+                return Some(path.elements.clone());
+            }
+
+            if depth == max_depth {
+                break;
+            }
+
+            let mut next_beam = vec![];
+
+            for path in beam.iter() {
+                let candidates = self.candidates(SentenceDirection::Forward, &path.elements);
+
+                for (element, probability) in candidates.elements.iter() {
+                    // A bridge can't go through the end of a sentence.
+                    if let StateElement::Marker(_) = element {
+                        continue;
+                    }
+
+                    if *probability <= 0f64 {
+                        continue;
+                    }
+
+                    let mut elements = path.elements.clone();
+                    elements.push((*element).clone());
+
+                    next_beam.push(BridgePath {
+                        elements,
+                        log_prob: path.log_prob + probability.ln(),
+                    });
+                }
+            }
+
+            if next_beam.is_empty() {
+                break;
+            }
+
+            next_beam.sort_by(|a, b| b.log_prob.total_cmp(&a.log_prob));
+            next_beam.truncate(beam_width);
+            beam = next_beam;
+        }
+
+        None
+    }
+}
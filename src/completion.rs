@@ -0,0 +1,92 @@
+use crate::brain::{sentence_to_string, tokenize, Brain};
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// Continue a text instead of replying to it.
+    ///
+    /// The last words of `prefix` are used as the current state, backing off
+    /// to shorter states when the brain doesn't know the longer ones, and only
+    /// the words following the prefix are generated.
+    ///
+    /// Returns `None` if the brain couldn't add any word to the prefix.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(continuation) = brain.continue_text("I think that").unwrap() {
+    ///     println!("I think that {}", continuation);
+    /// }
+    /// ```
+    ///
+    pub fn continue_text(&mut self, prefix: impl AsRef<str>) -> Result<Option<String>> {
+        let sentence = prefix_sentence(prefix.as_ref());
+        let prefix_len = sentence.len();
+
+        let mut rng = self.rng.clone();
+        let sentence =
+            self.complete_sentence(&mut Walk::new(&mut rng, self.config.sampling), sentence);
+        self.rng = rng;
+
+        let continuation = sentence_to_string(&sentence[prefix_len..]);
+
+        if continuation.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(continuation))
+    }
+
+    /// Get the `k` most likely words following `prefix`, with their probabilities.
+    ///
+    /// The lookup backs off to shorter states the same way generation does.
+    /// Probabilities are computed over every possible next element, including
+    /// the end of the sentence, so they may not sum up to 1.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// for (word, probability) in brain.next_word_candidates("I think", 5) {
+    ///     println!("{}: {:.2}", word, probability);
+    /// }
+    /// ```
+    ///
+    pub fn next_word_candidates(&self, prefix: impl AsRef<str>, k: usize) -> Vec<(String, f64)> {
+        let sentence = prefix_sentence(prefix.as_ref());
+
+        let mut candidates: Vec<(String, f64)> = self
+            .candidates(SentenceDirection::Forward, &sentence)
+            .elements
+            .iter()
+            .filter_map(|(element, probability)| {
+                if let StateElement::Word(word) = element {
+                    Some((word.clone(), *probability))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(k);
+        candidates
+    }
+}
+
+// A prefix is the beginning of a sentence.
+fn prefix_sentence(prefix: &str) -> Vec<StateElement> {
+    let mut sentence = vec![StateElement::Marker(SentenceMarker::Start)];
+    sentence.append(&mut tokenize(prefix));
+    sentence
+}
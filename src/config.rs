@@ -1,6 +1,7 @@
 use crate::brain_prelude::*;
 
 pub const SPLIT_CHARS: [char; 2] = [' ', '\n'];
+pub const SENTENCE_END_CHARS: [char; 3] = ['.', '!', '?'];
 
 /// This struct let you configure a [`Brain`].
 ///
@@ -8,7 +9,7 @@ pub const SPLIT_CHARS: [char; 2] = [' ', '\n'];
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
 /// use rustkov::prelude::BrainConfig;
 ///
 /// let config = BrainConfig {
@@ -23,6 +24,7 @@ pub const SPLIT_CHARS: [char; 2] = [' ', '\n'];
 ///
 /// [`Brain`]: crate::brain::Brain
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct BrainConfig {
     /// Represents the max items we use as a single state
     /// when ingesting an input.
@@ -30,6 +32,31 @@ pub struct BrainConfig {
     /// The greater it is, the bigger the brain struct will be.
     pub max_ingestion_state_size: usize,
 
+    /// The most states the brain keeps, to bound its memory usage.
+    ///
+    /// Past it, the rarest states are forgotten, down to 90% of it.
+    /// 0 lets the brain grow without limit.
+    pub max_states: usize,
+
+    /// The most line hashes the brain keeps for [`reject_training_lines`],
+    /// and the most hashes of runs of words for [`max_copy_ratio`].
+    ///
+    /// Past it, any of them are forgotten, down to 90% of it, so their lines
+    /// may be given as replies again. 0 keeps every hash.
+    ///
+    /// [`reject_training_lines`]: crate::config::BrainConfig::reject_training_lines
+    /// [`max_copy_ratio`]: crate::config::BrainConfig::max_copy_ratio
+    pub max_line_hashes: usize,
+
+    /// The most lines the brain remembers for [`track_provenance`].
+    ///
+    /// Past it, the oldest lines of the biggest sources are dropped, down to 90% of it.
+    /// What they taught is still known, but can't be forgotten with their source
+    /// anymore. 0 keeps every line.
+    ///
+    /// [`track_provenance`]: crate::config::BrainConfig::track_provenance
+    pub max_source_lines: usize,
+
     /// Allow the brain to learn from inputs.
     pub training: bool,
 
@@ -44,44 +71,198 @@ pub struct BrainConfig {
     /// This setting will be used when constructing a sentence.
     ///
     /// The brain will only take states with a length greater than
-    /// this setting.
+    /// or equal to this setting.
     pub min_generation_state_size: usize,
 
     /// This setting will be used when constructing a sentence.
     ///
     /// The brain will only take states with a length smaller than
-    /// this setting.
+    /// or equal to this setting.
     pub max_generation_state_size: usize,
 
     /// Let you ban forbidden words from appearing in responses.
     pub excluded_words: Vec<String>,
+
+    /// How many partial paths [`Brain::bridge`] keeps at each step
+    /// of its search.
+    ///
+    /// A wider beam finds more bridges, at the cost of speed.
+    ///
+    /// [`Brain::bridge`]: crate::brain::Brain::bridge
+    pub bridge_beam_width: usize,
+
+    /// How [`Brain::score`] handles transitions the brain has never seen.
+    ///
+    /// [`Brain::score`]: crate::brain::Brain::score
+    pub unseen_transitions: UnseenTransitions,
+
+    /// How the brain picks the next word of a sentence.
+    ///
+    /// See [`Sampling`].
+    ///
+    /// [`Sampling`]: crate::sampling::Sampling
+    pub sampling: Sampling,
+
+    /// How the brain combines states of different lengths,
+    /// within the generation range, when looking for the next word.
+    ///
+    /// See [`Backoff`].
+    ///
+    /// [`Backoff`]: crate::enums::Backoff
+    pub backoff: Backoff,
+
+    /// Lowers the chance of picking a word used recently in the sentence.
+    ///
+    /// The weight of such words is divided by this setting, so 1 disables it.
+    pub repetition_penalty: f64,
+
+    /// How many of the last elements of a sentence are checked
+    /// for the repetition penalty.
+    pub repetition_window: usize,
+
+    /// Ends a sentence as soon as it repeats a group of this many elements,
+    /// to get out of loops like "and the and the and the".
+    ///
+    /// 0 disables loop detection.
+    pub loop_ngram_size: usize,
+
+    /// The maximum number of elements in a generated sentence,
+    /// after which it is ended.
+    ///
+    /// 0 disables this limit.
+    pub max_sentence_length: usize,
+
+    /// Reject replies that are the same as the input.
+    pub reject_input_echo: bool,
+
+    /// Reject replies that are the same as a line the brain learnt from.
+    ///
+    /// The brain only remembers the lines it learns while this is enabled,
+    /// so lines learnt before aren't rejected.
+    pub reject_training_lines: bool,
+
+    /// Reject replies sharing too many consecutive words with the input
+    /// or with a line the brain learnt from.
+    ///
+    /// It is the share of the reply's words that can be copied, so 1 disables it.
+    /// Like [`reject_training_lines`], only the lines learnt while this is enabled
+    /// are checked.
+    ///
+    /// [`reject_training_lines`]: crate::config::BrainConfig::reject_training_lines
+    pub max_copy_ratio: f64,
+
+    /// How many consecutive words the brain remembers together from the lines
+    /// it learns, for [`max_copy_ratio`].
+    ///
+    /// Copies of training lines shorter than this aren't found. It should be
+    /// greater than [`max_generation_state_size`] + 1, as any run of that many
+    /// words of a reply comes from a learnt line. 0 only checks the input.
+    ///
+    /// [`max_copy_ratio`]: crate::config::BrainConfig::max_copy_ratio
+    /// [`max_generation_state_size`]: crate::config::BrainConfig::max_generation_state_size
+    pub copy_shingle_size: usize,
+
+    /// How many sentences the brain builds when looking for a reply
+    /// that isn't rejected, before giving up.
+    pub max_generation_attempts: usize,
+
+    /// How many of its last replies the brain remembers, to avoid
+    /// replying the same thing over and over.
+    ///
+    /// 0 disables this memory.
+    pub response_history_size: usize,
+
+    /// Reject replies at least this similar to a remembered reply.
+    ///
+    /// The similarity is the share of words two replies have in common,
+    /// from 0 to 1.
+    pub response_similarity_threshold: f64,
+
+    /// How long replies should be.
+    ///
+    /// See [`ReplyLength`].
+    ///
+    /// [`ReplyLength`]: crate::enums::ReplyLength
+    pub reply_length: ReplyLength,
+
+    /// Split learnt lines into sentences, after words ending with
+    /// a punctuation mark, and remember which sentences follow each other.
+    ///
+    /// It lets replies of several sentences flow like the lines they come from.
+    pub learn_sentence_links: bool,
+
+    /// Remember the lines learnt with [`Brain::learn_from`], for each source,
+    /// so everything learnt from a source can be forgotten.
+    ///
+    /// The lines are saved along with the brain.
+    ///
+    /// [`Brain::learn_from`]: crate::brain::Brain::learn_from
+    pub track_provenance: bool,
+
+    /// How many epochs it takes for what the brain learnt to weigh half as much,
+    /// so recent lines weigh more than old ones.
+    ///
+    /// Epochs are advanced with [`Brain::advance_epoch`]. 0 disables decay.
+    /// Each state decays the elements following it on its own, before states
+    /// are combined by the [`Backoff`].
+    ///
+    /// [`Brain::advance_epoch`]: crate::brain::Brain::advance_epoch
+    /// [`Backoff`]: crate::enums::Backoff
+    pub decay_half_life: f64,
+
+    /// How many lines can wait to be learnt by a [`SharedBrain`].
+    ///
+    /// Lines sent while the queue is full are dropped.
+    ///
+    /// [`SharedBrain`]: crate::shared::SharedBrain
+    pub learning_queue_size: usize,
 }
 impl Default for BrainConfig {
     fn default() -> Self {
         Self {
             max_ingestion_state_size: 5,
+            max_states: 0,
+            max_line_hashes: 0,
+            max_source_lines: 0,
             training: false,
             mute: false,
             reply_rate: 1f64,
             min_generation_state_size: 2,
             max_generation_state_size: 4,
             excluded_words: vec![],
+            bridge_beam_width: 64,
+            unseen_transitions: UnseenTransitions::Floor(1e-6),
+            sampling: Default::default(),
+            backoff: Backoff::LongestMatch,
+            repetition_penalty: 1f64,
+            repetition_window: 10,
+            loop_ngram_size: 4,
+            max_sentence_length: 100,
+            reject_input_echo: false,
+            reject_training_lines: false,
+            max_copy_ratio: 1f64,
+            copy_shingle_size: 6,
+            max_generation_attempts: 5,
+            response_history_size: 0,
+            response_similarity_threshold: 0.8,
+            reply_length: ReplyLength::Sentences(1),
+            learn_sentence_links: false,
+            track_provenance: false,
+            decay_half_life: 0f64,
+            learning_queue_size: 1024,
         }
     }
 }
 impl BrainConfig {
-    pub(crate) fn get_state_range(&self) -> Range<usize> {
-        Range {
-            start: self.min_generation_state_size,
-            end: self.max_generation_state_size,
-        }
+    pub(crate) fn get_state_range(&self) -> RangeInclusive<usize> {
+        self.min_generation_state_size..=self.max_generation_state_size
     }
 
     /// Load a config from disk.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::BrainConfig;
     ///
     /// let config = BrainConfig::from_file("path/to/config.yml").unwrap();
@@ -102,9 +283,10 @@ impl BrainConfig {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rustkov::prelude::BrainConfig;
     ///
+    /// # let config = BrainConfig::default();
     /// config.to_file("path/to/config.yml").unwrap();
     ///
     /// ```
@@ -113,7 +295,7 @@ impl BrainConfig {
 
         let data = serde_yaml::to_string(&self).unwrap();
 
-        config_file.write(data.as_bytes())?;
+        config_file.write_all(data.as_bytes())?;
 
         Ok(())
     }
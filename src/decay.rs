@@ -0,0 +1,316 @@
+use crate::brain::{for_each_window, Brain};
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// The current epoch of the brain, starting at 0.
+    ///
+    /// See [`decay_half_life`].
+    ///
+    /// [`decay_half_life`]: crate::config::BrainConfig::decay_half_life
+    pub fn epoch(&self) -> u64 {
+        self.memory.epoch
+    }
+
+    /// Move on to the next epoch, so what was learnt until now weighs a bit less.
+    ///
+    /// It's up to you to decide how long an epoch lasts, like a day or
+    /// a thousand lines. See [`decay_half_life`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::from_file("brain.bin").unwrap();
+    /// brain.config.decay_half_life = 30.0;
+    ///
+    /// // Once a day:
+    /// brain.advance_epoch();
+    /// brain.decay();
+    /// ```
+    ///
+    /// [`decay_half_life`]: crate::config::BrainConfig::decay_half_life
+    pub fn advance_epoch(&mut self) -> u64 {
+        self.memory.epoch += 1;
+        self.memory.epoch
+    }
+
+    /// Apply the decay of the weights to the occurences the brain holds,
+    /// forgetting the ones that decayed to nothing.
+    ///
+    /// Weights already decay when generating, this keeps the brain from growing
+    /// with what it no longer uses. Like [`Brain::prune`], words left without a state
+    /// of their own are forgotten everywhere.
+    ///
+    /// Does nothing if [`decay_half_life`] is 0.
+    /// Returns how many states were removed.
+    ///
+    /// [`Brain::prune`]: crate::brain::Brain::prune
+    /// [`decay_half_life`]: crate::config::BrainConfig::decay_half_life
+    pub fn decay(&mut self) -> usize {
+        let half_life = self.config.decay_half_life;
+
+        if half_life <= 0f64 {
+            return 0;
+        }
+
+        let states = self.state_transitions.len();
+        let epoch = self.memory.epoch;
+        let last_seen = &mut self.memory.last_seen;
+
+        self.state_transitions.retain(|state, transition| {
+            for direction in [SentenceDirection::Backward, SentenceDirection::Forward] {
+                let elements = match direction {
+                    SentenceDirection::Backward => &mut transition.prev,
+                    SentenceDirection::Forward => &mut transition.next,
+                };
+
+                for (element, occurence) in elements.iter_mut() {
+                    let seen = last_seen
+                        .get(state)
+                        .and_then(|last_seen| last_seen.seen(direction, element));
+                    let decayed = decayed(seen, *occurence, epoch, half_life);
+
+                    // Occurences only go down once rounding changes them,
+                    // while the decayed ones keep decaying from when they were seen.
+                    if decayed.round() < *occurence as f64 {
+                        *occurence = decayed.round() as usize;
+                        last_seen.entry(state.clone()).or_default().set(
+                            direction,
+                            element,
+                            Seen {
+                                epoch,
+                                occurences: decayed,
+                            },
+                        );
+                    }
+                }
+
+                elements.retain(|(_, occurence)| *occurence > 0);
+            }
+
+            transition.bound_tags();
+            !transition.is_empty()
+        });
+
+        self.remove_dangling();
+
+        states - self.state_transitions.len()
+    }
+
+    // Remember the elements around the states of a sentence were seen now,
+    // before the sentence is learnt.
+    pub(crate) fn see_states(&mut self, sentence: Vec<StateElement>) {
+        let half_life = self.config.decay_half_life;
+        let epoch = self.memory.epoch;
+        let state_transitions = &self.state_transitions;
+        let last_seen = &mut self.memory.last_seen;
+
+        for_each_window(
+            sentence,
+            self.config.max_ingestion_state_size,
+            |state, prev_element, next_element| {
+                if !last_seen.contains_key(state) {
+                    last_seen.insert(State(state.to_vec()), Default::default());
+                }
+
+                let last_seen = last_seen.get_mut(state).unwrap();
+                let transition = state_transitions.get(state);
+
+                for (direction, element) in [
+                    (SentenceDirection::Backward, prev_element),
+                    (SentenceDirection::Forward, next_element),
+                ] {
+                    if *element == StateElement::Marker(SentenceMarker::Placeholder) {
+                        continue;
+                    }
+
+                    // A state can be seen several times in a sentence, before
+                    // any of them is learnt, so the decayed occurences aren't
+                    // bounded by the learnt ones here.
+                    let occurences = match last_seen.seen(direction, element) {
+                        Some(seen) => seen.decayed(epoch, half_life),
+                        None => {
+                            let occurences = transition
+                                .map_or(0, |transition| transition.count(direction, element));
+                            decayed(None, occurences, epoch, half_life)
+                        }
+                    };
+
+                    last_seen.set(
+                        direction,
+                        element,
+                        Seen {
+                            epoch,
+                            occurences: occurences + 1f64,
+                        },
+                    );
+                }
+            },
+        );
+    }
+
+    // Combine when the elements around the states of another brain were seen
+    // with when they were seen by this one, before the occurences of the other
+    // brain are added, multiplied by `weight`.
+    pub(crate) fn absorb_last_seen(&mut self, other: &Brain, weight: f64) {
+        let half_life = self.config.decay_half_life;
+        let epoch = self.memory.epoch.max(other.memory.epoch);
+
+        for (state, other_last_seen) in other.memory.last_seen.iter() {
+            // Such states aren't merged.
+            if state.0.len() > self.config.max_ingestion_state_size {
+                continue;
+            }
+
+            let transition = self.state_transitions.get(state);
+            let other_transition = other.state_transitions.get(state);
+            let last_seen = self.memory.last_seen.entry(state.clone()).or_default();
+
+            for direction in [SentenceDirection::Backward, SentenceDirection::Forward] {
+                let other_seen = match direction {
+                    SentenceDirection::Backward => &other_last_seen.prev,
+                    SentenceDirection::Forward => &other_last_seen.next,
+                };
+
+                for (element, other_seen) in other_seen.iter() {
+                    let count = |transition: Option<&Transistion>| {
+                        transition.map_or(0, |transition| transition.count(direction, element))
+                    };
+
+                    let occurences = decayed(
+                        last_seen.seen(direction, element),
+                        count(transition),
+                        epoch,
+                        half_life,
+                    ) + weight
+                        * decayed(Some(other_seen), count(other_transition), epoch, half_life);
+
+                    last_seen.set(direction, element, Seen { epoch, occurences });
+                }
+            }
+        }
+    }
+
+    // The occurences of the elements following `state` in a transition,
+    // decayed according to when they were seen after it, when weights decay.
+    pub(crate) fn decayed_occurences<'a>(
+        &self,
+        direction: SentenceDirection,
+        state: &[StateElement],
+        transition: &'a Transistion,
+    ) -> Vec<(&'a StateElement, f64)> {
+        let half_life = self.config.decay_half_life;
+        let last_seen = self.memory.last_seen.get(state);
+
+        transition
+            .elements(direction)
+            .iter()
+            .map(|(element, occurence)| {
+                if half_life <= 0f64 {
+                    return (element, *occurence as f64);
+                }
+
+                let seen = last_seen.and_then(|last_seen| last_seen.seen(direction, element));
+
+                (
+                    element,
+                    decayed(seen, *occurence, self.memory.epoch, half_life),
+                )
+            })
+            .collect()
+    }
+}
+
+// How much an element seen `occurences` times weighs at `epoch`,
+// given when it was last seen.
+//
+// Elements learnt before weights decayed are taken as seen at epoch 0. Unlearning
+// lowers the occurences without touching when they were seen, so they bound it.
+fn decayed(seen: Option<&Seen>, occurences: usize, epoch: u64, half_life: f64) -> f64 {
+    let seen = match seen {
+        Some(seen) => Seen {
+            occurences: seen.occurences.min(occurences as f64),
+            ..*seen
+        },
+        None => Seen {
+            epoch: 0,
+            occurences: occurences as f64,
+        },
+    };
+
+    seen.decayed(epoch, half_life)
+}
+
+// How much something seen `age` epochs ago still weighs.
+pub(crate) fn decay_factor(age: u64, half_life: f64) -> f64 {
+    if half_life <= 0f64 {
+        return 1f64;
+    }
+
+    0.5f64.powf(age as f64 / half_life)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::{SentenceMarker, StateElement};
+    use crate::prelude::*;
+
+    fn decaying_brain(half_life: f64) -> Brain {
+        let mut brain = Brain::new();
+        brain.config.decay_half_life = half_life;
+        brain
+    }
+
+    #[test]
+    fn occurences_halve_every_half_life() {
+        let mut brain = decaying_brain(2.0);
+
+        for _ in 0..8 {
+            brain.learn("the cat sat", None, None);
+        }
+
+        let state = vec![
+            StateElement::Marker(SentenceMarker::Start),
+            StateElement::Word("the".to_string()),
+        ];
+        let count = |brain: &Brain| brain.state_transitions[state.as_slice()].next[0].1;
+
+        for expected in [4, 2, 1] {
+            brain.advance_epoch();
+            brain.advance_epoch();
+            brain.decay();
+
+            assert_eq!(count(&brain), expected);
+        }
+    }
+
+    #[test]
+    fn fresh_occurences_dont_revive_old_ones() {
+        let mut brain = decaying_brain(5.0);
+
+        for _ in 0..100 {
+            brain.learn("x y z", None, None);
+        }
+
+        for _ in 0..50 {
+            brain.advance_epoch();
+        }
+
+        brain.learn("x y z", None, None);
+        brain.learn("x y w", None, None);
+
+        let candidates = brain.next_word_candidates("x y", 2);
+        let probability = |word: &str| {
+            candidates
+                .iter()
+                .find(|(candidate, _)| candidate == word)
+                .unwrap()
+                .1
+        };
+
+        // z weighs 1 + 100 / 2^10, w weighs 1.
+        assert!((probability("z") - 0.523).abs() < 0.01);
+        assert!((probability("w") - 0.477).abs() < 0.01);
+    }
+}
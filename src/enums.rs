@@ -1,10 +1,19 @@
 use crate::brain_prelude::*;
 
-#[derive(Debug, Clone)]
+/// The side of a sentence the brain is working on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SentenceDirection {
     Backward,
     Forward,
 }
+impl SentenceDirection {
+    pub(crate) fn reverse(self) -> Self {
+        match self {
+            Self::Backward => Self::Forward,
+            Self::Forward => Self::Backward,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SentenceMarker {
@@ -18,3 +27,70 @@ pub enum StateElement {
     Marker(SentenceMarker),
     Word(String),
 }
+impl std::fmt::Display for StateElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(word) => f.write_str(word),
+            Self::Marker(SentenceMarker::Start) => f.write_str("<start>"),
+            Self::Marker(SentenceMarker::End) => f.write_str("<end>"),
+            Self::Marker(SentenceMarker::Placeholder) => f.write_str("<placeholder>"),
+        }
+    }
+}
+
+/// How the brain scores a transition it has never seen.
+///
+/// Used by [`Brain::score`].
+///
+/// [`Brain::score`]: crate::brain::Brain::score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UnseenTransitions {
+    /// Give unseen transitions this fixed probability.
+    /// Seen transitions keep their raw probability.
+    Floor(f64),
+
+    /// Add this pseudo-count to every word of the vocabulary
+    /// (additive smoothing), so unseen transitions get a small share
+    /// of the probability mass.
+    ///
+    /// A pseudo-count of 0 or less is treated as the smallest positive one.
+    Smoothing(f64),
+}
+
+/// How the brain combines states of different lengths when looking
+/// for the next word of a sentence.
+///
+/// Only states within the generation range of [`BrainConfig`] are used.
+///
+/// [`BrainConfig`]: crate::config::BrainConfig
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Backoff {
+    /// Only use the longest known state.
+    LongestMatch,
+
+    /// Mix the probabilities of every known state, weighted by their length.
+    ///
+    /// The first weight applies to states of [`min_generation_state_size`] elements,
+    /// the second one to the length above, and so on.
+    /// Lengths without a weight get a weight of 1.
+    ///
+    /// [`min_generation_state_size`]: crate::config::BrainConfig::min_generation_state_size
+    Interpolated(Vec<f64>),
+
+    /// Kneser–Ney-style smoothing.
+    ///
+    /// The occurences of longer states are lowered by this discount, and the
+    /// probability mass it frees is given to shorter states. The shortest state
+    /// gives it to words according to the number of different words they follow.
+    KneserNey(f64),
+}
+
+/// How long the replies of the brain should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplyLength {
+    /// Reply with this many sentences.
+    Sentences(usize),
+
+    /// Add sentences to the reply until it has at least this many words.
+    Words(usize),
+}
@@ -0,0 +1,173 @@
+use crate::brain::{tokenize, Brain};
+use crate::brain_prelude::*;
+
+/// The result of evaluating a [`Brain`] against held-out text.
+///
+/// Use it to compare brains trained with different settings on the same data:
+/// the lower the perplexity, the better the brain predicts unseen text.
+///
+/// [`Brain`]: crate::brain::Brain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evaluation {
+    /// Number of evaluated lines.
+    pub lines: usize,
+
+    /// Number of evaluated words.
+    pub words: usize,
+
+    /// Perplexity of the brain over the evaluated text.
+    ///
+    /// The end of each sentence counts as a token.
+    pub perplexity: f64,
+
+    /// The share of evaluated words unknown to the brain.
+    pub oov_rate: f64,
+
+    /// For each state size, from 1 to [`max_ingestion_state_size`],
+    /// the share of the evaluated text's states known to the brain.
+    ///
+    /// [`max_ingestion_state_size`]: crate::config::BrainConfig::max_ingestion_state_size
+    pub coverage: Vec<(usize, f64)>,
+}
+
+impl Brain {
+    /// Evaluate the brain against a held-out text file.
+    ///
+    /// The file is read line by line, like [`Brain::from_dataset`], but nothing is learnt from it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("train.txt").unwrap()
+    ///     .get();
+    ///
+    /// let evaluation = brain.evaluate("test.txt").unwrap();
+    ///
+    /// println!("perplexity: {:.2}", evaluation.perplexity);
+    /// ```
+    ///
+    /// [`Brain::from_dataset`]: crate::brain::Brain::from_dataset
+    pub fn evaluate(&self, test_path: impl AsRef<str>) -> Result<Evaluation> {
+        let test_file = File::open(test_path.as_ref())?;
+        let lines: Vec<String> = BufReader::new(test_file)
+            .lines()
+            .map_while(|line| line.ok())
+            .collect();
+
+        Ok(self.evaluate_lines(&lines))
+    }
+
+    /// Split a dataset into train and test lines, train the brain on the first ones
+    /// and evaluate it against the others.
+    ///
+    /// `test_ratio` is the share of lines kept for evaluation. Lines are shuffled
+    /// before the split.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new();
+    /// brain.config.max_ingestion_state_size = 3;
+    ///
+    /// let evaluation = brain.evaluate_split("your_dataset.txt", 0.1).unwrap();
+    ///
+    /// println!("perplexity: {:.2}", evaluation.perplexity);
+    /// ```
+    ///
+    pub fn evaluate_split(
+        &mut self,
+        dataset_path: impl AsRef<str>,
+        test_ratio: f64,
+    ) -> Result<Evaluation> {
+        let dataset_file = File::open(dataset_path.as_ref())?;
+        let mut lines: Vec<String> = BufReader::new(dataset_file)
+            .lines()
+            .map_while(|line| line.ok())
+            .collect();
+
+        lines.shuffle(&mut self.rng);
+
+        let test_len = (lines.len() as f64 * test_ratio.clamp(0f64, 1f64)).round() as usize;
+        let train_lines = lines.split_off(test_len);
+
+        for line in train_lines.iter() {
+            self.ingest(line);
+        }
+
+        Ok(self.evaluate_lines(&lines))
+    }
+
+    /// Evaluate the brain against held-out lines.
+    ///
+    /// See [`Brain::evaluate`].
+    ///
+    /// [`Brain::evaluate`]: crate::brain::Brain::evaluate
+    pub fn evaluate_lines(&self, lines: &[impl AsRef<str>]) -> Evaluation {
+        let known_words = self.stats().get_words();
+        let vocabulary_size = self.vocabulary_size();
+        let max_state_size = self.config.max_ingestion_state_size;
+
+        let mut evaluated_lines = 0;
+        let mut words = 0;
+        let mut unknown_words = 0;
+        let mut tokens = 0;
+        let mut log_prob = 0f64;
+        let mut known_states = vec![0usize; max_state_size];
+        let mut total_states = vec![0usize; max_state_size];
+
+        for line in lines.iter() {
+            let split = tokenize(line.as_ref());
+
+            if split.is_empty() {
+                continue;
+            }
+
+            evaluated_lines += 1;
+            words += split.len();
+            unknown_words += split
+                .iter()
+                .filter(|element| match element {
+                    StateElement::Word(word) => !known_words.contains(word.as_str()),
+                    StateElement::Marker(_) => false,
+                })
+                .count();
+
+            let mut elements = vec![StateElement::Marker(SentenceMarker::Start)];
+            elements.extend(split.iter().cloned());
+            elements.push(StateElement::Marker(SentenceMarker::End));
+
+            for state_size in 1..=max_state_size {
+                total_states[state_size - 1] += elements.windows(state_size).count();
+                known_states[state_size - 1] += elements
+                    .windows(state_size)
+                    .filter(|state| self.state_transitions.contains_key(*state))
+                    .count();
+            }
+
+            let score = self.score_elements(split, vocabulary_size);
+            tokens += score.tokens.len() + 1;
+            log_prob += score.log_prob;
+        }
+
+        Evaluation {
+            lines: evaluated_lines,
+            words,
+            perplexity: (-log_prob / tokens.max(1) as f64).exp(),
+            oov_rate: unknown_words as f64 / words.max(1) as f64,
+            coverage: (1..=max_state_size)
+                .map(|state_size| {
+                    (
+                        state_size,
+                        known_states[state_size - 1] as f64
+                            / total_states[state_size - 1].max(1) as f64,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
@@ -7,7 +7,7 @@
 //!
 //! # Examples
 //!
-//! ```
+//! ```no_run
 //! use rustkov::prelude::*;
 //!
 //! fn main() -> Result<()> {
@@ -27,6 +27,8 @@
 //!     if let Some(response) = brain.generate("Hello there!")? {
 //!         println!("{}", response);
 //!     }
+//!
+//!     Ok(())
 //! }
 //!
 //! ```
@@ -37,7 +39,7 @@
 //!
 //! Add the following to your `Cargo.toml` file:
 //!
-//! ```
+//! ```toml
 //! [dependencies]
 //! rustkov = "0.1.0"
 //! ```
@@ -46,19 +48,43 @@
 //! [`BrainStats`]: crate::stats::BrainStats
 //! [`Brain::stats`]: crate::brain::Brain::stats
 
+#[cfg(feature = "async")]
+mod async_brain;
+mod babble;
+mod batch;
 mod brain;
 mod brain_components;
+mod bridge;
+mod completion;
 mod config;
+mod decay;
 mod enums;
 mod error;
+mod evaluation;
+mod merge;
+mod originality;
+mod parallel;
+mod provenance;
+mod pruning;
+mod repetition;
+mod sampling;
+mod scoring;
+mod shared;
+mod snapshot;
 mod stats;
+mod stream;
+mod tags;
+#[cfg(test)]
+mod test_utils;
+mod trace;
+mod unlearn;
 
 mod brain_prelude {
     pub use std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet, VecDeque},
         fs::{File, OpenOptions},
         io::{BufRead, BufReader, Read, Write},
-        ops::Range,
+        ops::{Range, RangeInclusive},
     };
 
     pub use rand::prelude::{IteratorRandom, RngCore, SliceRandom};
@@ -69,13 +95,25 @@ mod brain_prelude {
     pub use crate::config::*;
     pub use crate::enums::*;
     pub use crate::error::*;
+    pub use crate::sampling::*;
     pub use crate::stats::*;
-    pub use crate::stats::*;
+    pub use crate::trace::*;
 }
 
 pub mod prelude {
+    #[cfg(feature = "async")]
+    pub use crate::async_brain::AsyncBrain;
     pub use crate::brain::Brain;
     pub use crate::config::BrainConfig;
+    pub use crate::enums::{Backoff, ReplyLength, SentenceDirection, UnseenTransitions};
     pub use crate::error::{Error, Result};
+    pub use crate::evaluation::Evaluation;
+    pub use crate::merge::MergeReport;
+    pub use crate::sampling::Sampling;
+    pub use crate::scoring::{SentenceScore, TokenScore};
     pub use crate::stats::BrainStats;
+    pub use crate::shared::SharedBrain;
+    pub use crate::snapshot::SnapshotBrain;
+    pub use crate::stream::WordStream;
+    pub use crate::trace::{ExplainedReply, GenerationTrace, SentenceTrace, TraceStep};
 }
@@ -0,0 +1,201 @@
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+/// What merging brains changed, returned by [`Brain::merge`].
+///
+/// [`Brain::merge`]: crate::brain::Brain::merge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// How many states the brain didn't know before.
+    pub new_states: usize,
+
+    /// How many states the brain already knew, and got more occurences for.
+    pub shared_states: usize,
+
+    /// How many states were left out because they are longer than the
+    /// [`max_ingestion_state_size`] of the brain.
+    ///
+    /// [`max_ingestion_state_size`]: crate::config::BrainConfig::max_ingestion_state_size
+    pub skipped_states: usize,
+}
+
+impl Brain {
+    /// Add what another brain learnt to this one.
+    ///
+    /// The occurences of the other brain are multiplied by `weight` and rounded,
+    /// so 1 merges them as they are, and 0.5 makes the other brain count
+    /// half as much as this one.
+    ///
+    /// States longer than the [`max_ingestion_state_size`] of this brain are
+    /// left out, so its longest states aren't only learnt from the other brain.
+    /// If the other brain was trained with a smaller size, the longest states
+    /// of this brain only hold its own occurences.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::from_file("community_a.bin").unwrap();
+    /// let other = Brain::from_file("community_b.bin").unwrap();
+    ///
+    /// let report = brain.merge(&other, 0.5);
+    ///
+    /// println!("{} new states, {} shared", report.new_states, report.shared_states);
+    /// ```
+    ///
+    /// [`max_ingestion_state_size`]: crate::config::BrainConfig::max_ingestion_state_size
+    pub fn merge(&mut self, other: &Brain, weight: f64) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        self.absorb_last_seen(other, weight);
+
+        for (state, other_transition) in &other.state_transitions {
+            if state.0.len() > self.config.max_ingestion_state_size {
+                report.skipped_states += 1;
+                continue;
+            }
+
+            if let Some(transition) = self.state_transitions.get_mut(state) {
+                transition.absorb(other_transition, weight);
+                report.shared_states += 1;
+                continue;
+            }
+
+            let mut transition = Transistion::default();
+            transition.absorb(other_transition, weight);
+
+            // Every occurence might have been rounded down to nothing.
+            if !transition.is_empty() {
+                self.state_transitions.insert(state.clone(), transition);
+                report.new_states += 1;
+            }
+        }
+
+        self.memory.absorb(&other.memory, weight);
+        self.limit_states();
+        self.limit_lines();
+
+        report
+    }
+
+    /// Add what several brains learnt to this one, each with its own weight.
+    ///
+    /// See [`Brain::merge`]. The report adds up the reports of every merge,
+    /// so a state known by several brains is counted once per brain.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let a = Brain::from_file("community_a.bin").unwrap();
+    /// let b = Brain::from_file("community_b.bin").unwrap();
+    ///
+    /// let mut brain = Brain::new();
+    /// brain.merge_all([(&a, 1.0), (&b, 2.0)]);
+    /// ```
+    ///
+    /// [`Brain::merge`]: crate::brain::Brain::merge
+    pub fn merge_all<'a>(
+        &mut self,
+        others: impl IntoIterator<Item = (&'a Brain, f64)>,
+    ) -> MergeReport {
+        others
+            .into_iter()
+            .fold(MergeReport::default(), |mut total, (other, weight)| {
+                let report = self.merge(other, weight);
+
+                total.new_states += report.new_states;
+                total.shared_states += report.shared_states;
+                total.skipped_states += report.skipped_states;
+                total
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::{SentenceDirection, StateElement};
+    use crate::prelude::*;
+
+    fn word(word: &str) -> StateElement {
+        StateElement::Word(word.to_string())
+    }
+
+    // A brain that learnt "the cat sat on the mat" three times.
+    fn other_brain(max_ingestion_state_size: usize) -> Brain {
+        let mut other = Brain::new()
+            .config(BrainConfig {
+                max_ingestion_state_size,
+                ..Default::default()
+            })
+            .unwrap()
+            .get();
+
+        for _ in 0..3 {
+            other.ingest("the cat sat on the mat");
+        }
+        other
+    }
+
+    #[test]
+    fn merged_occurences_are_weighted_and_rounded() {
+        let other = other_brain(5);
+
+        // 3 × 0.5 rounds to 2.
+        let mut brain = Brain::new();
+        let report = brain.merge(&other, 0.5);
+        let transition = &brain.state_transitions[&[word("the")][..]];
+
+        assert_eq!(
+            transition.count(SentenceDirection::Forward, &word("cat")),
+            2
+        );
+        assert_eq!(report.new_states, other.state_transitions.len());
+
+        // Merging it again adds to what the brain knows.
+        let report = brain.merge(&other, 1f64);
+        let transition = &brain.state_transitions[&[word("the")][..]];
+
+        assert_eq!(
+            transition.count(SentenceDirection::Forward, &word("cat")),
+            5
+        );
+        assert_eq!(report.shared_states, other.state_transitions.len());
+
+        // 3 × 0.1 rounds to nothing, so no state is learnt.
+        let mut brain = Brain::new();
+        let report = brain.merge(&other, 0.1);
+
+        assert!(brain.state_transitions.is_empty());
+        assert_eq!(report.new_states, 0);
+    }
+
+    #[test]
+    fn states_longer_than_the_ingestion_size_are_skipped() {
+        let other = other_brain(5);
+        let mut brain = Brain::new()
+            .config(BrainConfig {
+                max_ingestion_state_size: 2,
+                ..Default::default()
+            })
+            .unwrap()
+            .get();
+
+        let report = brain.merge(&other, 1f64);
+        let longer = other
+            .state_transitions
+            .keys()
+            .filter(|state| state.0.len() > 2)
+            .count();
+
+        assert!(longer > 0);
+        assert_eq!(report.skipped_states, longer);
+        assert_eq!(report.new_states, other.state_transitions.len() - longer);
+        assert!(brain
+            .state_transitions
+            .keys()
+            .all(|state| state.0.len() <= 2));
+    }
+}
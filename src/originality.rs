@@ -0,0 +1,174 @@
+use crate::brain::{sentence_to_string, tokenize, Brain};
+use crate::brain_prelude::*;
+
+impl Brain {
+    // Whether a reply passes the originality checks of the configuration.
+    pub(crate) fn is_original(&self, reply: &str, input: &str) -> bool {
+        let input = sentence_to_string(&tokenize(input));
+
+        if self.config.reject_input_echo && reply == input {
+            return false;
+        }
+
+        if self.config.reject_training_lines && self.memory.line_hashes.contains(&line_hash(reply))
+        {
+            return false;
+        }
+
+        if self.config.max_copy_ratio < 1f64 {
+            let reply_words = words(reply);
+
+            if reply_words.is_empty() {
+                return true;
+            }
+
+            let copied = longest_common_substring(&reply_words, &words(&input))
+                .max(self.longest_learnt_run(&reply_words));
+
+            if copied as f64 / reply_words.len() as f64 > self.config.max_copy_ratio {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // The length of the longest run of words found in the lines learnt,
+    // as far as their shingles tell: it is made of consecutive shingles
+    // that were all learnt, though maybe from different lines.
+    fn longest_learnt_run(&self, words: &[&str]) -> usize {
+        let size = self.config.copy_shingle_size;
+        let mut longest = 0;
+        let mut run = 0;
+
+        for shingle in shingles(words, size) {
+            if self.memory.shingles.contains_key(&shingle) {
+                run += 1;
+                longest = longest.max(run + size - 1);
+            } else {
+                run = 0;
+            }
+        }
+
+        longest
+    }
+
+    // Whether a reply is too similar to one of the last replies.
+    pub(crate) fn is_recent_response(&self, reply: &str) -> bool {
+        self.recent_responses.iter().any(|response| {
+            similarity(reply, response) >= self.config.response_similarity_threshold
+        })
+    }
+
+    pub(crate) fn remember_response(&mut self, reply: &str) {
+        let history_size = self.config.response_history_size;
+
+        if history_size == 0 {
+            self.recent_responses.clear();
+            return;
+        }
+
+        self.recent_responses.push_back(reply.to_string());
+
+        while self.recent_responses.len() > history_size {
+            self.recent_responses.pop_front();
+        }
+    }
+
+    /// Forget the last replies of the brain, so they can be given again.
+    ///
+    /// See [`response_history_size`].
+    ///
+    /// [`response_history_size`]: crate::config::BrainConfig::response_history_size
+    pub fn forget_recent_responses(&mut self) {
+        self.recent_responses.clear();
+    }
+}
+
+// A stable hash of a line, so hashes saved to disk stay valid.
+//
+// This is FNV-1a.
+pub(crate) fn line_hash(line: &str) -> u64 {
+    line.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// The words of a line, as `sentence_to_string` joins them.
+pub(crate) fn words(line: &str) -> Vec<&str> {
+    line.split(' ').filter(|word| !word.is_empty()).collect()
+}
+
+// The hashes of every run of `size` words, in order.
+pub(crate) fn shingles(words: &[&str], size: usize) -> Vec<u64> {
+    if size == 0 {
+        return vec![];
+    }
+
+    words
+        .windows(size)
+        .map(|shingle| line_hash(&shingle.join(" ")))
+        .collect()
+}
+
+// The length of the longest run of words found in both `a` and `b`.
+fn longest_common_substring(a: &[&str], b: &[&str]) -> usize {
+    let mut longest = 0;
+    let mut previous = vec![0; b.len() + 1];
+
+    for a_word in a.iter() {
+        let mut current = vec![0; b.len() + 1];
+
+        for (j, b_word) in b.iter().enumerate() {
+            if a_word == b_word {
+                current[j + 1] = previous[j] + 1;
+                longest = longest.max(current[j + 1]);
+            }
+        }
+
+        previous = current;
+    }
+
+    longest
+}
+
+// The share of distinct words two replies have in common (Jaccard index).
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<&str> = words(a).into_iter().collect();
+    let b: HashSet<&str> = words(b).into_iter().collect();
+
+    let union = a.union(&b).count();
+
+    if union == 0 {
+        return 1f64;
+    }
+
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn copies_of_training_lines_are_rejected() {
+        let mut brain = Brain::new()
+            .config(BrainConfig {
+                max_copy_ratio: 0.5,
+                copy_shingle_size: 3,
+                ..Default::default()
+            })
+            .unwrap()
+            .get();
+
+        brain.ingest("the quick brown fox jumps over the lazy dog");
+
+        assert!(!brain.is_original("a quick brown fox jumps over it", ""));
+        assert!(brain.is_original("a quick brown fox sleeps all day", ""));
+        assert!(!brain.is_original("my cat sat there", "my cat sat on a mat"));
+
+        // Forgetting the line forgets its runs of words too.
+        assert!(brain.unlearn("the quick brown fox jumps over the lazy dog"));
+        assert!(brain.is_original("a quick brown fox jumps over it", ""));
+    }
+}
@@ -0,0 +1,134 @@
+use std::thread;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+// How many lines each worker learns at once.
+const CHUNK_LINES: usize = 10_000;
+
+impl Brain {
+    /// Create a brain from a dataset, using every core.
+    ///
+    /// Chunks of the dataset are learnt by separate brains in parallel,
+    /// which are then merged into this one. The result is exactly the same
+    /// as [`Brain::from_dataset`], unless [`max_states`] or [`max_line_hashes`]
+    /// are set: the caps are then applied once each batch is merged,
+    /// so other states or hashes may be evicted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let dataset_path = "path/to/your/dataset.txt";
+    ///
+    /// let brain = Brain::new()
+    ///                 .from_dataset_parallel(dataset_path).unwrap()
+    ///                 .get();
+    /// ```
+    ///
+    /// [`Brain::from_dataset`]: crate::brain::Brain::from_dataset
+    /// [`max_states`]: crate::config::BrainConfig::max_states
+    /// [`max_line_hashes`]: crate::config::BrainConfig::max_line_hashes
+    pub fn from_dataset_parallel(&mut self, dataset_path: impl AsRef<str>) -> Result<&mut Self> {
+        let dataset_path = dataset_path.as_ref();
+
+        println!("Getting a new brain from '{}`...", dataset_path);
+        let dataset_file = File::open(dataset_path)?;
+        let mut lines = BufReader::new(dataset_file)
+            .lines()
+            .map_while(|line| line.ok());
+
+        // Only a chunk per core is read at once, to bound memory usage.
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let batch_lines = CHUNK_LINES * threads;
+
+        loop {
+            let batch: Vec<String> = lines.by_ref().take(batch_lines).collect();
+
+            if batch.is_empty() {
+                break;
+            }
+
+            let partials: Vec<Brain> = thread::scope(|scope| {
+                let workers: Vec<_> = batch
+                    .chunks(CHUNK_LINES)
+                    .map(|chunk| scope.spawn(|| self.learn_apart(chunk)))
+                    .collect();
+
+                workers
+                    .into_iter()
+                    .map(|worker| worker.join().unwrap())
+                    .collect()
+            });
+
+            // Merged in order, so elements are kept in the order they were first seen.
+            for partial in partials {
+                self.absorb(partial);
+            }
+        }
+        Ok(self)
+    }
+
+    // A new brain, with the same configuration, that learnt `lines`.
+    //
+    // It isn't capped, the caps apply once it is absorbed.
+    pub(crate) fn learn_apart(&self, lines: &[impl AsRef<str>]) -> Brain {
+        let mut partial = Brain {
+            config: BrainConfig {
+                max_states: 0,
+                max_line_hashes: 0,
+                ..self.config.clone()
+            },
+            ..Default::default()
+        };
+        partial.memory.epoch = self.memory.epoch;
+
+        for line in lines {
+            partial.ingest(line.as_ref());
+        }
+        partial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CHUNK_LINES;
+    use crate::prelude::*;
+    use crate::test_utils::{dataset, dataset_file};
+
+    #[test]
+    fn parallel_ingestion_matches_sequential_ingestion() {
+        // Enough lines for several chunks, so partial brains get merged.
+        let lines = dataset(CHUNK_LINES * 2 + 500);
+        let path = dataset_file("parallel", &lines);
+
+        let config = BrainConfig {
+            learn_sentence_links: true,
+            reject_training_lines: true,
+            ..Default::default()
+        };
+
+        let sequential = Brain::new()
+            .config(config.clone())
+            .unwrap()
+            .from_dataset(&path)
+            .unwrap()
+            .get();
+        let parallel = Brain::new()
+            .config(config)
+            .unwrap()
+            .from_dataset_parallel(&path)
+            .unwrap()
+            .get();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(parallel.state_transitions, sequential.state_transitions);
+        assert_eq!(
+            parallel.memory.sentence_links,
+            sequential.memory.sentence_links
+        );
+        assert_eq!(parallel.memory.line_hashes, sequential.memory.line_hashes);
+    }
+}
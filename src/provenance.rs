@@ -0,0 +1,123 @@
+use crate::brain::Brain;
+
+impl Brain {
+    /// Learn a line, remembering it came from `source`.
+    ///
+    /// The source can be anything identifying where the line comes from,
+    /// like a user, a channel or a file.
+    /// It is only remembered when [`track_provenance`] is enabled, otherwise
+    /// the line is learnt as any other. See [`Brain::learn`] to also tag it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new();
+    /// brain.config.track_provenance = true;
+    ///
+    /// brain.learn_from("Hello there!", "user#1234");
+    /// ```
+    ///
+    /// [`track_provenance`]: crate::config::BrainConfig::track_provenance
+    /// [`Brain::learn`]: crate::brain::Brain::learn
+    pub fn learn_from(&mut self, line: impl AsRef<str>, source: impl AsRef<str>) {
+        self.learn(line, Some(source.as_ref()), None);
+    }
+
+    // Remember a learnt line came from `source`, when tracking provenance.
+    pub(crate) fn remember_source(&mut self, line: &str, source: &str) {
+        if self.config.track_provenance {
+            self.memory
+                .sources
+                .entry(source.to_string())
+                .or_default()
+                .push((line.to_string(), 1f64));
+
+            self.limit_lines();
+        }
+    }
+
+    /// Forget every line learnt from `source`, as with [`Brain::unlearn`].
+    ///
+    /// Only the lines learnt with [`Brain::learn_from`] while
+    /// [`track_provenance`] was enabled are known to come from a source.
+    /// Lines the brain doesn't fully hold anymore, for instance after
+    /// [`Brain::prune`], lose whatever is left of them.
+    ///
+    /// Lines of a brain merged with [`Brain::merge`] only lose the occurences
+    /// the merge added for them, multiplied by its weight and rounded down,
+    /// so what other sources taught is never forgotten.
+    ///
+    /// Returns how many lines were forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::from_file("brain.bin").unwrap();
+    ///
+    /// let forgotten = brain.forget_source("user#1234");
+    /// println!("Forgot {} lines", forgotten);
+    ///
+    /// brain.to_file("brain.bin").unwrap();
+    /// ```
+    ///
+    /// [`Brain::unlearn`]: crate::brain::Brain::unlearn
+    /// [`Brain::learn_from`]: crate::brain::Brain::learn_from
+    /// [`Brain::prune`]: crate::brain::Brain::prune
+    /// [`Brain::merge`]: crate::brain::Brain::merge
+    /// [`track_provenance`]: crate::config::BrainConfig::track_provenance
+    pub fn forget_source(&mut self, source: impl AsRef<str>) -> usize {
+        let lines = self
+            .memory
+            .sources
+            .remove(source.as_ref())
+            .unwrap_or_default();
+
+        let forgotten = lines.len();
+
+        // Lines learnt as they are are unlearnt the same way, when the brain holds them.
+        let left: Vec<(String, f64)> = lines
+            .into_iter()
+            .filter(|(line, weight)| *weight != 1f64 || !self.unlearn_line(line))
+            .collect();
+
+        self.forget_lines(&left);
+
+        forgotten
+    }
+
+    /// The sources the brain learnt lines from.
+    ///
+    /// See [`Brain::learn_from`].
+    ///
+    /// [`Brain::learn_from`]: crate::brain::Brain::learn_from
+    pub fn sources(&self) -> impl Iterator<Item = &str> {
+        self.memory.sources.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn forgetting_a_merged_source_keeps_what_others_taught() {
+        for weight in [0.4, 1.0, 2.0] {
+            let mut brain = Brain::new();
+            brain.ingest("hello there friend");
+
+            let mut other = Brain::new();
+            other.config.track_provenance = true;
+            other.learn_from("hello there friend", "bob");
+
+            let before = brain.clone();
+            brain.merge(&other, weight);
+            brain.forget_source("bob");
+
+            assert_eq!(brain.state_transitions, before.state_transitions);
+        }
+    }
+}
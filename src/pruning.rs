@@ -0,0 +1,260 @@
+use std::cmp::Reverse;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// Forget the rare states and transitions of the brain.
+    ///
+    /// Every element seen less than `min_count` times after a state is removed,
+    /// then every state seen less than `min_count` times.
+    /// Words left without a state of their own are forgotten everywhere,
+    /// so generation can always go on from any element it picks.
+    ///
+    /// Returns how many states were removed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let removed = brain.prune(2);
+    /// println!("Removed {} states", removed);
+    /// ```
+    pub fn prune(&mut self, min_count: usize) -> usize {
+        let states = self.state_transitions.len();
+
+        self.state_transitions.retain(|_, transition| {
+            transition.forget_rare(min_count);
+            transition.occurences() >= min_count && !transition.is_empty()
+        });
+
+        self.remove_dangling();
+
+        states - self.state_transitions.len()
+    }
+
+    // Evict the rarest states once there are more than `max_states`,
+    // down to 90% of it so evictions don't happen on every line.
+    //
+    // States of several elements go first, as single elements are needed
+    // to keep generating after any of them.
+    pub(crate) fn limit_states(&mut self) {
+        let max_states = self.config.max_states;
+
+        if max_states == 0 || self.state_transitions.len() <= max_states {
+            return;
+        }
+
+        let mut states: Vec<(&State, usize)> = self
+            .state_transitions
+            .iter()
+            .map(|(state, transition)| (state, transition.occurences()))
+            .collect();
+
+        states.sort_by_key(|(state, occurences)| {
+            (state.0.len() == 1, *occurences, Reverse(state.0.len()))
+        });
+
+        let evicted: Vec<State> = states
+            .iter()
+            .take(self.state_transitions.len() - max_states * 9 / 10)
+            .map(|(state, _)| (*state).clone())
+            .collect();
+
+        for state in evicted {
+            self.state_transitions.remove(&state);
+        }
+
+        self.remove_dangling();
+    }
+
+    // Forget the lines remembered for originality checks and provenance once
+    // there are more than their own caps, down to 90% of them.
+    //
+    // Any line hash or run of words can go. Lines of sources go oldest first from the biggest
+    // sources, and what they taught is kept: it just can't be forgotten
+    // with the source anymore.
+    pub(crate) fn limit_lines(&mut self) {
+        let max_hashes = self.config.max_line_hashes;
+        let line_hashes = self.memory.line_hashes.len();
+
+        if max_hashes > 0 && line_hashes > max_hashes {
+            let evicted: Vec<u64> = self
+                .memory
+                .line_hashes
+                .iter()
+                .take(line_hashes - max_hashes * 9 / 10)
+                .copied()
+                .collect();
+
+            for hash in evicted {
+                self.memory.line_hashes.remove(&hash);
+            }
+        }
+
+        let shingles = self.memory.shingles.len();
+
+        if max_hashes > 0 && shingles > max_hashes {
+            let evicted: Vec<u64> = self
+                .memory
+                .shingles
+                .keys()
+                .take(shingles - max_hashes * 9 / 10)
+                .copied()
+                .collect();
+
+            for shingle in evicted {
+                self.memory.shingles.remove(&shingle);
+            }
+        }
+
+        let max_lines = self.config.max_source_lines;
+        let source_lines: usize = self.memory.sources.values().map(Vec::len).sum();
+
+        if max_lines == 0 || source_lines <= max_lines {
+            return;
+        }
+
+        for _ in 0..source_lines - max_lines * 9 / 10 {
+            let lines = self
+                .memory
+                .sources
+                .values_mut()
+                .max_by_key(|lines| lines.len())
+                .unwrap();
+
+            lines.remove(0);
+        }
+
+        self.memory.sources.retain(|_, lines| !lines.is_empty());
+    }
+
+    // Forget the words without a state of their own that can be walked from
+    // in both directions, until every word left has one.
+    pub(crate) fn remove_dangling(&mut self) {
+        loop {
+            let dead: HashSet<StateElement> = self
+                .state_transitions
+                .iter()
+                .flat_map(|(state, transition)| {
+                    state
+                        .0
+                        .iter()
+                        .chain(transition.prev.iter().map(|(element, _)| element))
+                        .chain(transition.next.iter().map(|(element, _)| element))
+                })
+                .filter(|element| matches!(element, StateElement::Word(_)))
+                .filter(|element| {
+                    !self
+                        .state_transitions
+                        .get(std::slice::from_ref(*element))
+                        .is_some_and(|transition| {
+                            !transition.prev.is_empty() && !transition.next.is_empty()
+                        })
+                })
+                .cloned()
+                .collect();
+
+            if dead.is_empty() {
+                break;
+            }
+
+            forget_elements(&mut self.state_transitions, &dead);
+
+            for element in dead.iter() {
+                self.memory.forget_word(&element.to_string());
+            }
+        }
+
+        // The epochs elements were last seen only follow what the brain still knows.
+        let state_transitions = &self.state_transitions;
+
+        self.memory
+            .last_seen
+            .retain(|state, last_seen| match state_transitions.get(state) {
+                Some(transition) => {
+                    last_seen.forget_missing(transition);
+                    true
+                }
+                None => false,
+            });
+    }
+}
+
+// Remove every state and transition element involving any of `elements`.
+pub(crate) fn forget_elements(
+    state_transitions: &mut HashMap<State, Transistion>,
+    elements: &HashSet<StateElement>,
+) {
+    state_transitions.retain(|state, transition| {
+        if state.0.iter().any(|element| elements.contains(element)) {
+            return false;
+        }
+
+        transition.forget_elements(elements);
+        !transition.is_empty()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::StateElement;
+    use crate::prelude::*;
+    use crate::test_utils::dataset;
+
+    // Every word of the brain has a state of its own, which can be walked
+    // from in both directions.
+    fn assert_no_dangling_words(brain: &Brain) {
+        for (state, transition) in brain.state_transitions.iter() {
+            let elements = state
+                .0
+                .iter()
+                .chain(transition.prev.iter().map(|(element, _)| element))
+                .chain(transition.next.iter().map(|(element, _)| element));
+
+            for element in elements {
+                if let StateElement::Word(_) = element {
+                    let own = &brain.state_transitions[std::slice::from_ref(element)];
+                    assert!(!own.prev.is_empty() && !own.next.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn eviction_leaves_no_dangling_words() {
+        let mut brain = Brain::new();
+        brain.config.max_states = 300;
+
+        for line in dataset(2000) {
+            brain.learn(&line, None, None);
+            assert!(brain.state_transitions.len() <= 300);
+        }
+        assert_no_dangling_words(&brain);
+
+        brain.prune(3);
+        assert_no_dangling_words(&brain);
+    }
+
+    #[test]
+    fn source_lines_are_dropped_without_unlearning_them() {
+        let mut capped = Brain::new();
+        capped.config.track_provenance = true;
+        capped.config.max_source_lines = 10;
+        let mut uncapped = capped.clone();
+        uncapped.config.max_source_lines = 0;
+
+        for line in dataset(100) {
+            capped.learn_from(&line, "user");
+            uncapped.learn_from(&line, "user");
+        }
+
+        assert!(capped.memory.sources["user"].len() <= 10);
+        assert_eq!(capped.state_transitions, uncapped.state_transitions);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+impl Brain {
+    // Lower the weight of the candidates used recently at the edge of the sentence,
+    // according to the repetition penalty.
+    pub(crate) fn penalize_repetitions(
+        &self,
+        direction: SentenceDirection,
+        sentence: &[StateElement],
+        candidates: &mut [(&StateElement, f64)],
+    ) {
+        let penalty = self.config.repetition_penalty;
+
+        if penalty <= 0f64 || penalty == 1f64 {
+            return;
+        }
+
+        let window = self.config.repetition_window.min(sentence.len());
+        let recent = match direction {
+            SentenceDirection::Backward => &sentence[..window],
+            SentenceDirection::Forward => &sentence[sentence.len() - window..],
+        };
+
+        for (element, weight) in candidates.iter_mut() {
+            if let StateElement::Word(_) = element {
+                if recent.contains(element) {
+                    *weight /= penalty;
+                }
+            }
+        }
+    }
+
+    // Whether the walk in `direction` should stop here, because the sentence
+    // is too long or started looping.
+    //
+    // Only the generated elements can loop: the ones in `given`, that the walk
+    // started from, are neither compared nor removed. When it loops, the element
+    // that completed the repetition is removed, so the sentence can be ended cleanly.
+    pub(crate) fn stop_walk(
+        &self,
+        direction: SentenceDirection,
+        sentence: &mut Vec<StateElement>,
+        given: &Range<usize>,
+    ) -> bool {
+        let max_length = self.config.max_sentence_length;
+
+        if max_length > 0 && sentence.len() >= max_length {
+            return true;
+        }
+
+        let n = self.config.loop_ngram_size;
+
+        if n == 0 || sentence.len() <= n {
+            return false;
+        }
+
+        let generated = |start: usize| start + n <= given.start || start >= given.end;
+
+        let looping = match direction {
+            SentenceDirection::Backward => {
+                let edge = &sentence[..n];
+                generated(0)
+                    && sentence
+                        .windows(n)
+                        .enumerate()
+                        .skip(1)
+                        .any(|(start, window)| generated(start) && window == edge)
+            }
+            SentenceDirection::Forward => {
+                let edge_start = sentence.len() - n;
+                let edge = &sentence[edge_start..];
+                generated(edge_start)
+                    && sentence[..sentence.len() - 1]
+                        .windows(n)
+                        .enumerate()
+                        .any(|(start, window)| generated(start) && window == edge)
+            }
+        };
+
+        if looping {
+            match direction {
+                SentenceDirection::Backward => sentence.remove(0),
+                SentenceDirection::Forward => sentence.pop().unwrap(),
+            };
+        }
+
+        looping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn loops_are_only_detected_in_generated_elements() {
+        let mut brain = Brain::new();
+        brain.ingest("ha ha ha ha ha ha ha ha lol");
+
+        for _ in 0..20 {
+            let continuation = brain.continue_text("ha ha ha ha ha ha").unwrap();
+            assert!(continuation.is_some());
+
+            let bridge = brain.bridge("ha", "lol", 10).unwrap().unwrap();
+            assert!(bridge.ends_with("ha lol"));
+        }
+    }
+}
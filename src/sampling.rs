@@ -0,0 +1,181 @@
+use crate::brain_prelude::*;
+
+/// This struct let you configure how the brain picks the next word
+/// of a sentence among the candidates of a state.
+///
+/// The default samples candidates according to their raw occurences.
+/// Lower the temperature, or truncate the candidates with `top_k` or `top_p`,
+/// to get more conservative replies.
+///
+/// # Example
+///
+/// ```
+/// use rustkov::prelude::{Brain, Sampling};
+///
+/// let mut brain = Brain::new();
+///
+/// brain.config.sampling = Sampling {
+///     temperature: 0.8,
+///     top_p: 0.9,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sampling {
+    /// Scales the weights of the candidates before sampling.
+    ///
+    /// Weights are raised to the power of `1 / temperature`: under 1 it favours
+    /// likely candidates, above 1 it flattens the distribution.
+    /// A temperature of 0 is the same as `greedy`.
+    pub temperature: f64,
+
+    /// Only keep the `top_k` most likely candidates.
+    ///
+    /// 0 disables this truncation.
+    pub top_k: usize,
+
+    /// Only keep the most likely candidates whose cumulated probability
+    /// reaches `top_p` (nucleus sampling).
+    ///
+    /// It should be `0 < top_p <= 1`. 1 disables this truncation.
+    pub top_p: f64,
+
+    /// Always pick the most likely candidate.
+    pub greedy: bool,
+}
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            temperature: 1f64,
+            top_k: 0,
+            top_p: 1f64,
+            greedy: false,
+        }
+    }
+}
+impl Sampling {
+    // Pick a candidate according to the sampling strategy.
+    //
+    // Returns `None` if there's no candidate to pick from.
+    pub(crate) fn sample<'a, T>(
+        &self,
+        rng: &mut dyn RngCore,
+        candidates: &[(&'a T, f64)],
+    ) -> Option<&'a T> {
+        if self.greedy || self.temperature <= 0f64 {
+            return candidates
+                .iter()
+                .filter(|(_, weight)| *weight > 0f64)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(candidate, _)| *candidate);
+        }
+
+        let mut candidates: Vec<(&T, f64)> = candidates
+            .iter()
+            .filter(|(_, weight)| *weight > 0f64)
+            .map(|(candidate, weight)| (*candidate, weight.powf(1f64 / self.temperature)))
+            .collect();
+
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        if self.top_k > 0 {
+            candidates.truncate(self.top_k);
+        }
+
+        if self.top_p < 1f64 {
+            let total: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+            let mut cumulated = 0f64;
+            let mut kept = 0;
+
+            for (_, weight) in candidates.iter() {
+                cumulated += weight / total;
+                kept += 1;
+
+                if cumulated >= self.top_p {
+                    break;
+                }
+            }
+
+            candidates.truncate(kept);
+        }
+
+        // Weights can overflow with very low temperatures,
+        // in which case the most likely candidate wins.
+        match candidates.choose_weighted(rng, |item| item.1) {
+            Ok((candidate, _)) => Some(*candidate),
+            Err(_) => candidates.first().map(|(candidate, _)| *candidate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::prelude::*;
+
+    const CANDIDATES: [(&str, f64); 4] = [("a", 5f64), ("b", 3f64), ("c", 1.5f64), ("d", 0.5f64)];
+
+    // The candidates picked over many draws.
+    fn picks(sampling: Sampling) -> Vec<&'static str> {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let candidates: Vec<(&&str, f64)> = CANDIDATES
+            .iter()
+            .map(|(candidate, weight)| (candidate, *weight))
+            .collect();
+
+        let mut picks: Vec<&str> = (0..1000)
+            .map(|_| *sampling.sample(&mut rng, &candidates).unwrap())
+            .collect();
+
+        picks.sort();
+        picks.dedup();
+        picks
+    }
+
+    #[test]
+    fn greedy_picks_the_most_likely_candidate() {
+        let greedy = Sampling {
+            greedy: true,
+            ..Default::default()
+        };
+        let frozen = Sampling {
+            temperature: 0f64,
+            ..Default::default()
+        };
+
+        assert_eq!(picks(greedy), ["a"]);
+        assert_eq!(picks(frozen), ["a"]);
+    }
+
+    #[test]
+    fn top_k_keeps_the_k_most_likely_candidates() {
+        let sampling = Sampling {
+            top_k: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(picks(sampling), ["a", "b"]);
+    }
+
+    #[test]
+    fn top_p_keeps_the_candidates_reaching_p() {
+        // "a" and "b" make 80% of the weight.
+        let sampling = Sampling {
+            top_p: 0.8,
+            ..Default::default()
+        };
+
+        assert_eq!(picks(sampling), ["a", "b"]);
+
+        let sampling = Sampling {
+            top_p: 0.81,
+            ..Default::default()
+        };
+
+        assert_eq!(picks(sampling), ["a", "b", "c"]);
+        assert_eq!(picks(Default::default()), ["a", "b", "c", "d"]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::iter;
+
+use crate::brain::{tokenize, Brain};
+use crate::brain_prelude::*;
+
+/// The score of a single word of a sentence, given the words before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenScore {
+    /// The scored word.
+    pub token: String,
+
+    /// The natural logarithm of the probability of this word.
+    pub log_prob: f64,
+
+    /// Whether the brain has ever seen this transition.
+    pub seen: bool,
+}
+
+/// The score of a sentence, returned by [`Brain::score`].
+///
+/// [`Brain::score`]: crate::brain::Brain::score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceScore {
+    /// The score of every word of the sentence, in order.
+    pub tokens: Vec<TokenScore>,
+
+    /// The log-probability of the sentence ending after its last word.
+    pub end_log_prob: f64,
+
+    /// The log-probability of the whole sentence,
+    /// end of sentence included.
+    pub log_prob: f64,
+}
+
+impl SentenceScore {
+    /// Returns the perplexity of the sentence.
+    ///
+    /// The lower it is, the less the brain is surprised by the sentence.
+    pub fn perplexity(&self) -> f64 {
+        (-self.log_prob / (self.tokens.len() + 1) as f64).exp()
+    }
+}
+
+impl Brain {
+    /// Compute how likely a sentence is, according to the brain.
+    ///
+    /// The text is walked word by word through the chain, with the same
+    /// state lookup as generation. Transitions the brain has never seen are
+    /// handled according to [`unseen_transitions`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let score = brain.score("Hello there!");
+    ///
+    /// for token in score.tokens.iter() {
+    ///     println!("{}: {:.2}", token.token, token.log_prob);
+    /// }
+    /// println!("total: {:.2}", score.log_prob);
+    /// ```
+    ///
+    /// [`unseen_transitions`]: crate::config::BrainConfig::unseen_transitions
+    pub fn score(&self, text: impl AsRef<str>) -> SentenceScore {
+        self.score_elements(tokenize(text.as_ref()), self.vocabulary_size())
+    }
+
+    // The number of elements a word can be followed by: every known word,
+    // plus the end of a sentence.
+    pub(crate) fn vocabulary_size(&self) -> usize {
+        match self.config.unseen_transitions {
+            UnseenTransitions::Floor(_) => 0,
+            UnseenTransitions::Smoothing(_) => self.stats().get_total_words() + 1,
+        }
+    }
+
+    pub(crate) fn score_elements(
+        &self,
+        words: Vec<StateElement>,
+        vocabulary_size: usize,
+    ) -> SentenceScore {
+        let mut sentence = vec![StateElement::Marker(SentenceMarker::Start)];
+        let mut tokens = vec![];
+        let mut end_log_prob = 0f64;
+
+        for element in words
+            .into_iter()
+            .chain(iter::once(StateElement::Marker(SentenceMarker::End)))
+        {
+            let (log_prob, seen) = self.transition_log_prob(&sentence, &element, vocabulary_size);
+
+            match &element {
+                StateElement::Word(word) => tokens.push(TokenScore {
+                    token: word.clone(),
+                    log_prob,
+                    seen,
+                }),
+                StateElement::Marker(_) => end_log_prob = log_prob,
+            }
+
+            sentence.push(element);
+        }
+
+        let log_prob = tokens.iter().map(|token| token.log_prob).sum::<f64>() + end_log_prob;
+
+        SentenceScore {
+            tokens,
+            end_log_prob,
+            log_prob,
+        }
+    }
+
+    // The log-probability of `element` following `sentence`,
+    // and whether this transition was seen at all.
+    fn transition_log_prob(
+        &self,
+        sentence: &[StateElement],
+        element: &StateElement,
+        vocabulary_size: usize,
+    ) -> (f64, bool) {
+        let candidates = self.candidates(SentenceDirection::Forward, sentence);
+        let probability = candidates.probability(element);
+        let occurences = candidates.occurences as f64;
+
+        let smoothed_probability = match self.config.unseen_transitions {
+            UnseenTransitions::Floor(floor) => {
+                if probability <= 0f64 {
+                    floor
+                } else {
+                    probability
+                }
+            }
+            UnseenTransitions::Smoothing(k) => {
+                // Without a positive pseudo-count, a state seen with no
+                // transition at all would give 0/0.
+                let k = k.max(f64::MIN_POSITIVE);
+
+                (probability * occurences + k) / (occurences + k * vocabulary_size as f64)
+            }
+        };
+
+        (smoothed_probability.ln(), probability > 0f64)
+    }
+}
@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+
+use rand::SeedableRng;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+thread_local! {
+    // Each thread generates with its own random generator, so generating
+    // only needs a shared reference to the brain.
+    static RNG: RefCell<ChaCha8Rng> = RefCell::new(ChaCha8Rng::from_entropy());
+}
+
+/// A brain that can be shared between threads.
+///
+/// Any number of threads can generate replies at the same time, while the
+/// lines to learn are queued and learnt one after another by a background
+/// thread. The queue holds up to [`learning_queue_size`] lines, and lines
+/// sent while it is full are dropped, so replying never waits for learning.
+///
+/// Cloning a `SharedBrain` gives another handle to the same brain.
+/// The background thread stops once every handle is dropped.
+///
+/// Replies are not remembered for the [`response_history_size`] check,
+/// though the replies remembered by the brain beforehand are still avoided.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::thread;
+/// use rustkov::prelude::{Brain, SharedBrain};
+///
+/// let mut brain = Brain::new()
+///     .from_dataset("your_dataset.txt").unwrap()
+///     .get();
+/// brain.config.training = true;
+///
+/// let shared = SharedBrain::new(brain);
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         thread::spawn(move || shared.generate("Hello there!").unwrap())
+///     })
+///     .collect();
+///
+/// for handle in handles {
+///     println!("{:?}", handle.join().unwrap());
+/// }
+/// ```
+///
+/// [`learning_queue_size`]: crate::config::BrainConfig::learning_queue_size
+/// [`response_history_size`]: crate::config::BrainConfig::response_history_size
+#[derive(Debug, Clone)]
+pub struct SharedBrain {
+    brain: Arc<RwLock<Brain>>,
+    learning_queue: SyncSender<String>,
+}
+
+impl SharedBrain {
+    /// Share a brain, starting the thread that learns queued lines.
+    pub fn new(brain: Brain) -> Self {
+        let (learning_queue, lines) = mpsc::sync_channel(brain.config.learning_queue_size);
+        let brain = Arc::new(RwLock::new(brain));
+
+        let learner = brain.clone();
+        thread::spawn(move || learn_queued_lines(&learner, lines));
+
+        Self {
+            brain,
+            learning_queue,
+        }
+    }
+
+    /// Generate a reponse from an input, like [`Brain::generate`].
+    ///
+    /// The input is queued to be learnt when [`training`] is enabled.
+    ///
+    /// [`Brain::generate`]: crate::brain::Brain::generate
+    /// [`training`]: crate::config::BrainConfig::training
+    pub fn generate(&self, input: impl AsRef<str>) -> Result<Option<String>> {
+        let input = input.as_ref();
+
+        let (reply, training) = {
+            let brain = self.read();
+            let reply = with_thread_rng(|rng| brain.generate_with_rng(input, rng))?;
+
+            (reply, brain.config.training)
+        };
+
+        if training {
+            self.learn(input);
+        }
+
+        Ok(reply)
+    }
+
+    /// Queue a line for the brain to learn.
+    ///
+    /// Returns `false` if the line was dropped because the queue is full.
+    pub fn learn(&self, line: impl Into<String>) -> bool {
+        self.learning_queue.try_send(line.into()).is_ok()
+    }
+
+    /// Lock the brain for reading, to use any of its `&self` methods.
+    ///
+    /// Learning waits while the lock is held.
+    pub fn read(&self) -> RwLockReadGuard<'_, Brain> {
+        self.brain.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Lock the brain for writing, to edit its configuration for instance.
+    ///
+    /// Generating and learning wait while the lock is held.
+    pub fn write(&self) -> RwLockWriteGuard<'_, Brain> {
+        self.brain.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Learn the queued lines until every `SharedBrain` handle is dropped.
+//
+// The brain is locked for each line on its own,
+// so readers never wait for more than a line to be learnt.
+fn learn_queued_lines(brain: &RwLock<Brain>, lines: Receiver<String>) {
+    while let Ok(line) = lines.recv() {
+        brain
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .ingest(&line);
+    }
+}
+
+// Run `f` with the random generator of the current thread.
+pub(crate) fn with_thread_rng<T>(f: impl FnOnce(&mut ChaCha8Rng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use arc_swap::ArcSwap;
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+/// A brain shared between threads as immutable snapshots.
+///
+/// Readers get the current version of the brain without ever waiting,
+/// and can keep it as long as they need. Changes are made to a copy
+/// of the brain, which then replaces the current version at once.
+/// An old version is dropped when its last reader is done with it.
+///
+/// Changes are made one after another, so none of them is lost.
+///
+/// Cloning a `SnapshotBrain` gives another handle to the same brain.
+///
+/// # Example
+///
+/// ```no_run
+/// use rand::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+/// use rustkov::prelude::{Brain, SnapshotBrain};
+///
+/// let brain = Brain::new()
+///     .from_dataset("your_dataset.txt").unwrap()
+///     .get();
+///
+/// let snapshots = SnapshotBrain::new(brain);
+///
+/// let retrain = {
+///     let snapshots = snapshots.clone();
+///     std::thread::spawn(move || snapshots.retrain("new_dataset.txt"))
+/// };
+///
+/// // Readers keep using the previous version during the retrain.
+/// let brain = snapshots.load();
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// println!("{:?}", brain.generate_with_rng("Hello there!", &mut rng).unwrap());
+///
+/// retrain.join().unwrap().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotBrain {
+    current: Arc<ArcSwap<Brain>>,
+
+    // Held while a new version is built, so concurrent changes
+    // don't overwrite each other.
+    writer: Arc<Mutex<()>>,
+}
+
+impl SnapshotBrain {
+    /// Share a brain as snapshots.
+    pub fn new(brain: Brain) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(brain)),
+            writer: Default::default(),
+        }
+    }
+
+    /// Get the current version of the brain.
+    ///
+    /// The snapshot is never changed, even when a new version replaces it.
+    pub fn load(&self) -> Arc<Brain> {
+        self.current.load_full()
+    }
+
+    /// Replace the current version of the brain.
+    pub fn store(&self, brain: Brain) {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+
+        self.current.store(Arc::new(brain));
+    }
+
+    /// Change a copy of the current version of the brain, then replace it.
+    ///
+    /// The current version is kept if `change` fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::{Brain, SnapshotBrain};
+    ///
+    /// let snapshots = SnapshotBrain::new(Brain::new());
+    ///
+    /// snapshots.update(|brain| {
+    ///     brain.config.reply_rate = 0.5;
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn update(&self, change: impl FnOnce(&mut Brain) -> Result<()>) -> Result<()> {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut brain = Brain::clone(&self.current.load());
+        change(&mut brain)?;
+
+        self.current.store(Arc::new(brain));
+        Ok(())
+    }
+
+    /// Learn a dataset, line by line, into a new version of the brain.
+    ///
+    /// See [`SnapshotBrain::update`].
+    ///
+    /// [`SnapshotBrain::update`]: crate::snapshot::SnapshotBrain::update
+    pub fn retrain(&self, dataset_path: impl AsRef<str>) -> Result<()> {
+        self.update(|brain| brain.from_dataset(dataset_path).map(|_| ()))
+    }
+}
@@ -8,11 +8,10 @@ use super::{brain_prelude::StateElement, prelude::Brain};
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
 /// use rustkov::prelude::Brain;
 ///
-/// let brain = Brain::from_file("path/to/brain.yml").unwrap()
-///                         .get();
+/// let brain = Brain::from_file("path/to/brain.bin").unwrap();
 ///
 /// let stats = brain.stats();
 ///
@@ -41,8 +40,8 @@ impl<'a> BrainStats<'a> {
     pub fn get_total_transitions(&self) -> usize {
         self.brain
             .state_transitions
-            .iter()
-            .map(|(_, transition)| transition.prev.len() + transition.next.len())
+            .values()
+            .map(|transition| transition.prev.len() + transition.next.len())
             .sum()
     }
 
@@ -51,12 +50,17 @@ impl<'a> BrainStats<'a> {
     /// It is useful to see if your chatbot will be able to
     /// construct unique sentences
     pub fn avg_transition_per_state(&self) -> f32 {
-        return self.get_total_transitions() as f32 / self.get_total_states() as f32;
+        self.get_total_transitions() as f32 / self.get_total_states() as f32
     }
 
     /// Retruns the total number of single words
     /// known to the brain.
     pub fn get_total_words(&self) -> usize {
+        self.get_words().len()
+    }
+
+    // Every single word known to the brain.
+    pub(crate) fn get_words(&self) -> HashSet<&'a str> {
         let mut words: Vec<&str> = vec![];
 
         self.brain.state_transitions.iter().for_each(|(state, _)| {
@@ -70,12 +74,11 @@ impl<'a> BrainStats<'a> {
                         None
                     }
                 })
-                .for_each(|ref word| {
+                .for_each(|word| {
                     words.push(word);
                 });
         });
 
-        let set: HashSet<_> = words.drain(..).collect();
-        set.len()
+        words.drain(..).collect()
     }
 }
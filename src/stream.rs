@@ -0,0 +1,150 @@
+use rand::{Rng, SeedableRng};
+
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+/// An iterator over the words of a reply, built as they are yielded.
+///
+/// Get one with [`Brain::generate_stream`].
+///
+/// With the `stream` feature, it is also a [`Stream`] for async code.
+///
+/// [`Brain::generate_stream`]: crate::brain::Brain::generate_stream
+/// [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+pub struct WordStream<'a> {
+    brain: &'a mut Brain,
+    rng: ChaCha8Rng,
+    sampling: Sampling,
+    sentence: Vec<StateElement>,
+    position: usize,
+
+    // Where the seed of the sentence is, as it wasn't generated.
+    seed: Range<usize>,
+
+    // The input to learn once the reply is over, when training.
+    input: Option<String>,
+}
+
+impl<'a> WordStream<'a> {
+    fn learn_input(&mut self) {
+        if let Some(input) = self.input.take() {
+            self.brain.ingest(&input);
+        }
+    }
+}
+
+impl<'a> Iterator for WordStream<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Keep an element ahead of the one yielded, so the walk can still
+            // take it back if it starts a loop.
+            while self.sentence.len() <= self.position + 1
+                && *self.sentence.last().unwrap() != StateElement::Marker(SentenceMarker::End)
+            {
+                let mut walk = Walk::new(&mut self.rng, self.sampling);
+                self.brain.step_forward(&mut walk, &mut self.sentence, &self.seed);
+            }
+
+            let element = match self.sentence.get(self.position) {
+                Some(element) => element,
+                None => {
+                    self.learn_input();
+                    return None;
+                }
+            };
+            self.position += 1;
+
+            if let StateElement::Word(word) = element {
+                return Some(word.clone());
+            }
+        }
+    }
+}
+
+impl<'a> Drop for WordStream<'a> {
+    fn drop(&mut self) {
+        self.learn_input();
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<'a> futures_core::Stream for WordStream<'a> {
+    type Item = String;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::task::Poll::Ready(self.get_mut().next())
+    }
+}
+
+impl Brain {
+    /// Generate a reponse from an input, word by word.
+    ///
+    /// The words before the seed of the sentence are built right away,
+    /// the following ones are built as the iterator is consumed,
+    /// so the reply can be displayed progressively or stopped early.
+    ///
+    /// It is subject to the [`mute`] and [`reply_rate`] checks, and the input is
+    /// learnt once the reply is over or the iterator is dropped, when [`training`]
+    /// is enabled.
+    /// Replies are a single sentence, and can't go through the originality
+    /// checks since their words are yielded before the sentence is complete.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if let Some(words) = brain.generate_stream("Hello there!").unwrap() {
+    ///     for word in words {
+    ///         print!("{} ", word);
+    ///     }
+    /// }
+    /// println!();
+    /// ```
+    ///
+    /// [`mute`]: crate::config::BrainConfig::mute
+    /// [`reply_rate`]: crate::config::BrainConfig::reply_rate
+    /// [`training`]: crate::config::BrainConfig::training
+    pub fn generate_stream(&mut self, input: impl AsRef<str>) -> Result<Option<WordStream<'_>>> {
+        let input = input.as_ref();
+
+        // using ! bool since the config is about reply chance, not reply non chance.
+        if self.state_transitions.is_empty()
+            || self.config.mute
+            || !self.rng.gen_bool(self.config.reply_rate)
+        {
+            if self.config.training {
+                self.ingest(input);
+            }
+
+            return Ok(None);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.rng.gen());
+        let sampling = self.config.sampling;
+
+        let mut walk = Walk::new(&mut rng, sampling);
+        let seed = self.seed_element(&mut walk, input);
+        let sentence = self.complete_backward(&mut walk, vec![seed]);
+        let input = self.config.training.then(|| input.to_string());
+
+        Ok(Some(WordStream {
+            brain: self,
+            rng,
+            sampling,
+            seed: sentence.len() - 1..sentence.len(),
+            sentence,
+            position: 0,
+            input,
+        }))
+    }
+}
@@ -0,0 +1,93 @@
+use crate::brain::Brain;
+use crate::brain_prelude::*;
+
+impl Brain {
+    /// Learn a line, remembering it was written with a tag,
+    /// like the name of its author.
+    ///
+    /// The line is learnt as any other, and also counted for the tag,
+    /// so replies can then talk like the lines of the tag with [`Brain::generate_as`].
+    /// See [`Brain::learn`] to also remember its source.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new();
+    ///
+    /// brain.learn_tagged("Hello there!", "obi-wan");
+    /// brain.learn_tagged("General Kenobi!", "grievous");
+    /// ```
+    ///
+    /// [`Brain::generate_as`]: crate::brain::Brain::generate_as
+    /// [`Brain::learn`]: crate::brain::Brain::learn
+    pub fn learn_tagged(&mut self, line: impl AsRef<str>, tag: impl AsRef<str>) {
+        self.learn(line, None, Some(tag.as_ref()));
+    }
+
+    /// Generate a reponse from an input, talking like the lines learnt with `tags`.
+    ///
+    /// At each step, the probabilities of the lines learnt with any of the tags
+    /// are blended with the ones of every line by `weight`, between 0 and 1:
+    /// 1 only follows the tags when they know what to say, 0 ignores them.
+    /// Unknown tags are ignored, so if none is known, this is the same as
+    /// [`Brain::generate`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::from_file("brain.bin").unwrap();
+    ///
+    /// if let Some(response) = brain.generate_as("Hello there!", &["obi-wan"], 0.8).unwrap() {
+    ///     println!("{}", response);
+    /// }
+    /// ```
+    ///
+    /// [`Brain::generate`]: crate::brain::Brain::generate
+    pub fn generate_as(
+        &mut self,
+        input: impl AsRef<str>,
+        tags: &[&str],
+        weight: f64,
+    ) -> Result<Option<String>> {
+        Ok(self
+            ._generate(
+                input,
+                false,
+                self.config.sampling,
+                false,
+                Some((tags, weight)),
+            )?
+            .map(|explained| explained.reply))
+    }
+
+    /// The tags the brain learnt lines with.
+    ///
+    /// See [`Brain::learn_tagged`].
+    ///
+    /// [`Brain::learn_tagged`]: crate::brain::Brain::learn_tagged
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.memory.tags.keys().map(String::as_str)
+    }
+
+    /// Forget which lines were learnt with a tag.
+    ///
+    /// The lines themselves are still known, use [`Brain::forget_source`]
+    /// to forget them too.
+    ///
+    /// Returns `false` if the tag wasn't known.
+    ///
+    /// [`Brain::forget_source`]: crate::brain::Brain::forget_source
+    pub fn forget_tag(&mut self, tag: impl AsRef<str>) -> bool {
+        let tag = tag.as_ref();
+
+        for transition in self.state_transitions.values_mut() {
+            transition.tags.retain(|(known, _)| known != tag);
+        }
+
+        self.memory.tags.remove(tag).is_some()
+    }
+}
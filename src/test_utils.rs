@@ -0,0 +1,29 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// Random lines of a few words, some ending sentences, the same on every run.
+pub(crate) fn dataset(lines: usize) -> Vec<String> {
+    let words = [
+        "the", "cat", "dog", "sat", "on", "a", "mat", "and", "ran", "away", "hello", "there.",
+        "why?", "it", "is", "fine!",
+    ];
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+
+    (0..lines)
+        .map(|_| {
+            (0..rng.gen_range(1..12))
+                .map(|_| *words.choose(&mut rng).unwrap())
+                .collect::<Vec<&str>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+// A dataset written to a file of its own, named after the test using it.
+pub(crate) fn dataset_file(name: &str, lines: &[String]) -> String {
+    let path = std::env::temp_dir().join(format!("rustkov-{}-{}.txt", name, std::process::id()));
+
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    path.to_string_lossy().into_owned()
+}
@@ -0,0 +1,140 @@
+use crate::brain_prelude::*;
+
+/// A reply, along with how the brain built it.
+///
+/// Returned by [`Brain::generate_explained`].
+///
+/// [`Brain::generate_explained`]: crate::brain::Brain::generate_explained
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainedReply {
+    /// The reply, as [`Brain::generate`] would return it.
+    ///
+    /// [`Brain::generate`]: crate::brain::Brain::generate
+    pub reply: String,
+
+    /// How the reply was built.
+    pub trace: GenerationTrace,
+}
+
+/// How the brain built a reply.
+///
+/// Markers are written `<start>` and `<end>` in states and elements.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationTrace {
+    /// How many replies were built, the rejected ones included.
+    pub attempts: usize,
+
+    /// The sentences of the reply, in order.
+    pub sentences: Vec<SentenceTrace>,
+}
+
+/// How the brain built a single sentence of a reply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceTrace {
+    /// The word the sentence was built around,
+    /// or `None` if it started from a random state.
+    pub seed_word: Option<String>,
+
+    /// The state the seed element was taken from.
+    pub seed_state: Vec<String>,
+
+    /// The element the sentence was built from, in both directions.
+    pub seed_element: String,
+
+    /// Every element added to the seed, in order.
+    pub steps: Vec<TraceStep>,
+}
+
+/// A single element added to a sentence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// The side of the sentence the element was added to.
+    pub direction: SentenceDirection,
+
+    /// The longest state used to find the element.
+    pub state: Vec<String>,
+
+    /// The length of this state.
+    pub order: usize,
+
+    /// How many elements could have been picked.
+    pub candidates: usize,
+
+    /// The probability of the picked element, before sampling adjustments
+    /// like the temperature or the repetition penalty.
+    pub probability: f64,
+
+    /// The picked element.
+    pub element: String,
+}
+
+// What a walk through the markov chain needs besides the brain:
+// randomness, how to pick elements, and optionally a trace of the steps.
+pub(crate) struct Walk<'a> {
+    pub(crate) rng: &'a mut dyn RngCore,
+    pub(crate) sampling: Sampling,
+    pub(crate) trace: Option<GenerationTrace>,
+
+    // The tags to talk like, and how much they weigh.
+    pub(crate) tags: Option<(&'a [&'a str], f64)>,
+}
+impl<'a> Walk<'a> {
+    pub(crate) fn new(rng: &'a mut dyn RngCore, sampling: Sampling) -> Self {
+        Self {
+            rng,
+            sampling,
+            trace: None,
+            tags: None,
+        }
+    }
+
+    pub(crate) fn traced(rng: &'a mut dyn RngCore, sampling: Sampling) -> Self {
+        Self {
+            rng,
+            sampling,
+            trace: Some(Default::default()),
+            tags: None,
+        }
+    }
+
+    pub(crate) fn trace_seed(
+        &mut self,
+        seed_word: Option<&str>,
+        seed_state: &[StateElement],
+        seed_element: &StateElement,
+    ) {
+        if let Some(trace) = &mut self.trace {
+            trace.sentences.push(SentenceTrace {
+                seed_word: seed_word.map(|word| word.to_string()),
+                seed_state: seed_state
+                    .iter()
+                    .map(|element| element.to_string())
+                    .collect(),
+                seed_element: seed_element.to_string(),
+                steps: vec![],
+            });
+        }
+    }
+
+    pub(crate) fn trace_step(&mut self, step: impl FnOnce() -> TraceStep) {
+        if let Some(sentence) = self
+            .trace
+            .as_mut()
+            .and_then(|trace| trace.sentences.last_mut())
+        {
+            sentence.steps.push(step());
+        }
+    }
+
+    // How many sentences are traced, to forget the ones built after
+    // with `truncate_trace`.
+    pub(crate) fn traced_sentences(&self) -> usize {
+        self.trace.as_ref().map_or(0, |trace| trace.sentences.len())
+    }
+
+    pub(crate) fn truncate_trace(&mut self, sentences: usize) {
+        if let Some(trace) = &mut self.trace {
+            trace.sentences.truncate(sentences);
+        }
+    }
+}
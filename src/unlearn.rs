@@ -0,0 +1,378 @@
+use crate::brain::{sentence_to_string, tokenize, Brain};
+use crate::brain_prelude::*;
+use crate::originality::line_hash;
+use crate::pruning::forget_elements;
+
+impl Brain {
+    /// Forget a line the brain learnt, as if it never did.
+    ///
+    /// Every occurence learning the line added is removed, along with the
+    /// states and elements left without any. If the line was learnt several
+    /// times, it is forgotten once.
+    ///
+    /// Returns `false`, and changes nothing, if the brain doesn't hold
+    /// everything learning the line would add, for instance because it never
+    /// learnt it, or because [`max_ingestion_state_size`] or
+    /// [`learn_sentence_links`] changed since.
+    ///
+    /// The line is also forgotten from one of the sources and one of the tags
+    /// it was learnt with, see [`Brain::learn`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// if brain.unlearn("my password is hunter2") {
+    ///     println!("Forgotten!");
+    /// }
+    /// ```
+    ///
+    /// [`max_ingestion_state_size`]: crate::config::BrainConfig::max_ingestion_state_size
+    /// [`learn_sentence_links`]: crate::config::BrainConfig::learn_sentence_links
+    /// [`Brain::learn`]: crate::brain::Brain::learn
+    pub fn unlearn(&mut self, line: impl AsRef<str>) -> bool {
+        let line = line.as_ref();
+
+        if !self.unlearn_line(line) {
+            return false;
+        }
+
+        let line = sentence_to_string(&tokenize(line));
+
+        for lines in self.memory.sources.values_mut() {
+            if let Some(index) = lines
+                .iter()
+                .position(|(source_line, _)| sentence_to_string(&tokenize(source_line)) == line)
+            {
+                lines.remove(index);
+                break;
+            }
+        }
+
+        self.memory.sources.retain(|_, lines| !lines.is_empty());
+
+        true
+    }
+
+    // Unlearn a line, leaving the sources alone.
+    pub(crate) fn unlearn_line(&mut self, line: &str) -> bool {
+        let learnt = self.learn_apart(&[line]);
+
+        if !chain_holds(&self.state_transitions, &learnt.state_transitions)
+            || !self.memory.holds(&learnt.memory)
+        {
+            return false;
+        }
+
+        self.subtract_learnt(line, &learnt);
+
+        true
+    }
+
+    // Remove whatever is left of what learning lines added, even when the
+    // brain doesn't hold all of it anymore, for instance after pruning.
+    //
+    // Each line was added with a weight, by merging brains, and never loses more
+    // than its occurences multiplied by this weight, rounded down.
+    pub(crate) fn forget_lines(&mut self, lines: &[(String, f64)]) {
+        for (line, weight) in lines {
+            let mut learnt = self.learn_apart(&[line]);
+
+            if *weight != 1f64 {
+                learnt
+                    .state_transitions
+                    .values_mut()
+                    .for_each(|transition| transition.scale_down(*weight));
+                learnt
+                    .state_transitions
+                    .retain(|_, transition| !transition.is_empty());
+                learnt.memory.scale_down(*weight);
+            }
+
+            self.subtract_learnt(line, &learnt);
+        }
+
+        self.remove_dangling();
+    }
+
+    // Remove the occurences `learnt` holds for `line`, as far as the brain holds them.
+    fn subtract_learnt(&mut self, line: &str, learnt: &Brain) {
+        let tag = self.learnt_tag(&learnt.state_transitions);
+
+        subtract_chain(
+            &mut self.state_transitions,
+            &learnt.state_transitions,
+            tag.as_deref(),
+        );
+
+        for state in learnt.state_transitions.keys() {
+            match self.state_transitions.get(state) {
+                Some(transition) => {
+                    if let Some(last_seen) = self.memory.last_seen.get_mut(state) {
+                        last_seen.forget_missing(transition);
+                    }
+                }
+                None => {
+                    self.memory.last_seen.remove(state);
+                }
+            }
+        }
+
+        if let Some(tag) = tag {
+            let lines = self.memory.tags.get_mut(&tag).unwrap();
+            *lines -= 1;
+
+            if *lines == 0 {
+                self.memory.tags.remove(&tag);
+            }
+        }
+
+        self.memory.subtract(&learnt.memory);
+
+        // A line learnt several times can still be given as a reply
+        // while another copy of it is held.
+        if !chain_holds(&self.state_transitions, &learnt.state_transitions) {
+            self.memory
+                .line_hashes
+                .remove(&line_hash(&sentence_to_string(&tokenize(line))));
+        }
+    }
+
+    // The tag a line was learnt with, given what learning it adds.
+    //
+    // A copy of the line learnt without any tag is unlearnt first,
+    // then the one of the first tag holding it, by name.
+    fn learnt_tag(&self, learnt: &HashMap<State, Transistion>) -> Option<String> {
+        let holds = |hold: &dyn Fn(&Transistion, &Transistion) -> bool| {
+            learnt.iter().all(|(state, learnt)| {
+                self.state_transitions
+                    .get(state)
+                    .is_some_and(|transition| hold(transition, learnt))
+            })
+        };
+
+        if holds(&|transition, learnt| transition.untagged_holds(learnt)) {
+            return None;
+        }
+
+        let mut tags: Vec<&String> = self.memory.tags.keys().collect();
+        tags.sort();
+
+        tags.into_iter()
+            .find(|tag| {
+                holds(&|transition, learnt| {
+                    transition
+                        .tag(tag)
+                        .is_some_and(|tagged| tagged.holds(learnt))
+                })
+            })
+            .cloned()
+    }
+
+    /// Forget every line of a dataset, as with [`Brain::unlearn`].
+    ///
+    /// Returns how many lines were forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// let forgotten = brain.unlearn_dataset("spam.txt").unwrap();
+    /// println!("Forgot {} lines", forgotten);
+    /// ```
+    ///
+    /// [`Brain::unlearn`]: crate::brain::Brain::unlearn
+    pub fn unlearn_dataset(&mut self, dataset_path: impl AsRef<str>) -> Result<usize> {
+        let dataset_file = File::open(dataset_path.as_ref())?;
+        let mut lines = BufReader::new(dataset_file).lines();
+        let mut forgotten = 0;
+
+        while let Some(Ok(line)) = lines.next() {
+            if self.unlearn(&line) {
+                forgotten += 1;
+            }
+        }
+        Ok(forgotten)
+    }
+
+    /// Forget every state and element involving a word.
+    ///
+    /// The word is split and lowercased like learnt lines, and each resulting
+    /// word is forgotten. States left without any element are removed,
+    /// along with the lines holding the word in the sources of the brain.
+    ///
+    /// Returns how many states were removed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rustkov::prelude::Brain;
+    ///
+    /// let mut brain = Brain::new()
+    ///     .from_dataset("your_dataset.txt").unwrap()
+    ///     .get();
+    ///
+    /// brain.forget_word("hunter2");
+    /// ```
+    pub fn forget_word(&mut self, word: impl AsRef<str>) -> usize {
+        let states = self.state_transitions.len();
+
+        let elements: HashSet<StateElement> = tokenize(word.as_ref()).into_iter().collect();
+
+        forget_elements(&mut self.state_transitions, &elements);
+
+        for element in elements.iter() {
+            self.memory.forget_word(&element.to_string());
+        }
+
+        for lines in self.memory.sources.values_mut() {
+            lines.retain(|(line, _)| !tokenize(line).iter().any(|word| elements.contains(word)));
+        }
+
+        self.memory.sources.retain(|_, lines| !lines.is_empty());
+
+        states - self.state_transitions.len()
+    }
+}
+
+// Whether a markov chain holds at least every occurence of another one.
+fn chain_holds(
+    state_transitions: &HashMap<State, Transistion>,
+    other: &HashMap<State, Transistion>,
+) -> bool {
+    other.iter().all(|(state, other_transition)| {
+        state_transitions
+            .get(state)
+            .is_some_and(|transition| transition.holds(other_transition))
+    })
+}
+
+// Remove the occurences of another markov chain, as far as the chain holds them,
+// along with the states left without any.
+//
+// They are counted out of `tag` as well, if they were learnt with it.
+fn subtract_chain(
+    state_transitions: &mut HashMap<State, Transistion>,
+    other: &HashMap<State, Transistion>,
+    tag: Option<&str>,
+) {
+    for (state, other_transition) in other {
+        if let Some(transition) = state_transitions.get_mut(state) {
+            transition.subtract(other_transition, tag);
+
+            if transition.is_empty() {
+                state_transitions.remove(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_utils::dataset;
+
+    fn trained_brain() -> Brain {
+        let mut brain = Brain::new()
+            .config(BrainConfig {
+                learn_sentence_links: true,
+                reject_training_lines: true,
+                ..Default::default()
+            })
+            .unwrap()
+            .get();
+
+        for line in dataset(500) {
+            brain.learn(&line, None, Some("dataset"));
+        }
+        brain
+    }
+
+    fn assert_same_brain(brain: &Brain, other: &Brain) {
+        assert_eq!(brain.state_transitions, other.state_transitions);
+        assert_eq!(brain.memory.sentence_links, other.memory.sentence_links);
+        assert_eq!(brain.memory.line_hashes, other.memory.line_hashes);
+        assert_eq!(brain.memory.tags, other.memory.tags);
+    }
+
+    #[test]
+    fn unlearn_reverses_learning() {
+        let mut brain = trained_brain();
+        let before = brain.clone();
+
+        let lines = [
+            "the cat sat on a mat. and ran away!",
+            "my password is hunter2",
+            "hello there.",
+        ];
+
+        for line in lines {
+            brain.learn(line, None, Some("dataset"));
+        }
+        for line in lines {
+            assert!(brain.unlearn(line));
+        }
+
+        assert_same_brain(&brain, &before);
+    }
+
+    #[test]
+    fn unlearn_forgets_one_copy_at_a_time() {
+        let mut brain = trained_brain();
+        let before = brain.clone();
+        let line = "my password is hunter2";
+
+        brain.learn(line, None, None);
+        brain.learn(line, None, None);
+
+        assert!(brain.unlearn(line));
+        assert_eq!(
+            brain.memory.line_hashes.len(),
+            before.memory.line_hashes.len() + 1
+        );
+
+        assert!(brain.unlearn(line));
+        assert_same_brain(&brain, &before);
+
+        assert!(!brain.unlearn(line));
+    }
+
+    #[test]
+    fn unlearn_only_takes_from_the_tags_that_learnt_the_line() {
+        let mut brain = trained_brain();
+        let line = "my password is hunter2";
+
+        brain.learn(line, None, Some("secrets"));
+        let tagged = brain.clone();
+
+        brain.learn(line, None, None);
+        brain.learn(line, None, Some("chat"));
+
+        // The untagged copy goes first, then the one of the first tag by name.
+        assert!(brain.unlearn(line));
+        assert!(brain.unlearn(line));
+        assert_same_brain(&brain, &tagged);
+
+        assert!(brain.unlearn(line));
+        assert!(!brain.tags().any(|tag| tag == "secrets"));
+    }
+
+    #[test]
+    fn unlearn_ignores_unknown_lines() {
+        let mut brain = trained_brain();
+        let before = brain.clone();
+
+        assert!(!brain.unlearn("my password is hunter2"));
+        assert_same_brain(&brain, &before);
+    }
+}